@@ -0,0 +1,631 @@
+use log::info;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::history::HistoryEntry;
+use crate::vocabulary::VocabularyCard;
+
+/// 笔记类型的字段，顺序即 Anki 中的字段顺序，第一个字段用于排序与查重
+const FIELDS: [&str; 4] = ["Front", "Back", "Example", "Phonetic"];
+
+/// 导出 Anki 牌组时使用的牌组名与笔记类型
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct AnkiConfig {
+    pub deck_name: String,
+    /// 笔记类型名称，名称不变时再次导出的笔记会更新到同一笔记类型
+    pub note_type: String,
+    /// 正面模板，可使用 `{{Front}}`、`{{Back}}`、`{{Example}}`、`{{Phonetic}}`
+    pub front_template: String,
+    pub back_template: String,
+    pub css: String,
+}
+
+impl Default for AnkiConfig {
+    fn default() -> Self {
+        Self {
+            deck_name: "Simple Translate".to_string(),
+            note_type: "Simple Translate".to_string(),
+            front_template: "{{Front}}".to_string(),
+            back_template: "{{FrontSide}}\n\n<hr id=answer>\n\n{{Back}}\n\
+                {{#Example}}<div class=\"example\">{{Example}}</div>{{/Example}}\n\
+                {{#Phonetic}}<div class=\"phonetic\">{{Phonetic}}</div>{{/Phonetic}}"
+                .to_string(),
+            css: ".card { font-family: arial; font-size: 20px; text-align: center; \
+                color: black; background-color: white; }\n\
+                .example { margin-top: 12px; font-size: 16px; font-style: italic; }\n\
+                .phonetic { margin-top: 8px; font-size: 16px; color: #888; }"
+                .to_string(),
+        }
+    }
+}
+
+/// 一条待导出的笔记，字段均为纯文本
+pub struct AnkiNote {
+    pub front: String,
+    pub back: String,
+    pub example: Option<String>,
+    pub phonetic: Option<String>,
+    pub source_lang: String,
+    pub target_lang: String,
+}
+
+impl From<&HistoryEntry> for AnkiNote {
+    fn from(entry: &HistoryEntry) -> Self {
+        Self {
+            front: entry.source_text.clone(),
+            back: entry.translation.clone(),
+            example: None,
+            phonetic: None,
+            source_lang: entry
+                .detected_lang
+                .clone()
+                .unwrap_or_else(|| entry.source_lang.clone()),
+            target_lang: entry.target_lang.clone(),
+        }
+    }
+}
+
+impl From<&VocabularyCard> for AnkiNote {
+    fn from(card: &VocabularyCard) -> Self {
+        Self {
+            front: card.word.clone(),
+            back: card.translation.clone(),
+            example: card.example.clone(),
+            phonetic: card.phonetic.clone(),
+            source_lang: card.source_lang.clone(),
+            target_lang: card.target_lang.clone(),
+        }
+    }
+}
+
+impl AnkiNote {
+    /// 标签：`simple_translate` 与语言方向，如 `en-zh`
+    fn tags(&self) -> String {
+        let pair = format!("{}-{}", self.source_lang, self.target_lang)
+            .to_lowercase()
+            .replace(char::is_whitespace, "_");
+        format!(" simple_translate {} ", pair)
+    }
+
+    /// 由正面与语言方向决定，再次导出同一个单词时 Anki 会更新而不是重复添加
+    fn guid(&self) -> String {
+        let digest = Sha256::new()
+            .chain_update(self.front.trim())
+            .chain_update([0])
+            .chain_update(&self.source_lang)
+            .chain_update([0])
+            .chain_update(&self.target_lang)
+            .finalize();
+        hex::encode(&digest[..8])
+    }
+
+    /// 排序字段：第一个字段去掉 HTML 后的文本，Anki 也用它计算查重校验和
+    fn sort_field(&self) -> String {
+        strip_html(&html(self.front.trim()))
+    }
+
+    fn fields(&self) -> String {
+        [
+            Some(self.front.trim()),
+            Some(self.back.trim()),
+            self.example.as_deref(),
+            self.phonetic.as_deref(),
+        ]
+        .iter()
+        .map(|field| html(field.unwrap_or_default()))
+        .collect::<Vec<_>>()
+        .join("\x1f")
+    }
+}
+
+/// Anki 的字段内容是 HTML
+fn html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\n', "<br>")
+}
+
+/// 去掉标签并还原实体，与 Anki 计算排序字段时的处理一致
+fn strip_html(field: &str) -> String {
+    let mut text = String::with_capacity(field.len());
+    let mut rest = field;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        match rest[start..].find('>') {
+            Some(end) => rest = &rest[start + end + 1..],
+            None => {
+                rest = &rest[start..];
+                break;
+            }
+        }
+    }
+    text.push_str(rest);
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Anki 用排序字段 SHA-1 的前 8 位十六进制数查重
+fn checksum(text: &str) -> i64 {
+    let digest = Sha1::digest(text.as_bytes());
+    i64::from(u32::from_be_bytes([
+        digest[0], digest[1], digest[2], digest[3],
+    ]))
+}
+
+/// 由名称得到固定的 ID，避开 Anki 用毫秒时间戳分配的范围，且不超过 JavaScript 的安全整数
+fn stable_id(kind: &str, name: &str) -> i64 {
+    let digest = Sha256::new()
+        .chain_update(kind)
+        .chain_update([0])
+        .chain_update(name)
+        .finalize();
+    let hash = u64::from_be_bytes(digest[..8].try_into().unwrap());
+    (1 << 50) + (hash % (1 << 50)) as i64
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+fn validate(config: &AnkiConfig) -> Result<(), String> {
+    if config.deck_name.trim().is_empty() || config.note_type.trim().is_empty() {
+        return Err("Anki deck name and note type must not be empty".to_string());
+    }
+    if required_fields(config).is_empty() {
+        return Err(
+            "The Anki front template must contain at least one field, e.g. {{Front}}".to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// 正面模板引用的字段，全部为空时 Anki 不会生成卡片；除 `{{Field}}` 外也识别
+/// 条件段 `{{#Field}}` 与带过滤器的 `{{text:Field}}`，`{{^Field}}` 只在字段为空时显示，不算在内
+fn required_fields(config: &AnkiConfig) -> Vec<usize> {
+    let referenced: Vec<&str> = config
+        .front_template
+        .split("{{")
+        .skip(1)
+        .filter_map(|tag| tag.split_once("}}").map(|(name, _)| name.trim()))
+        .filter(|name| !name.starts_with('/') && !name.starts_with('^'))
+        .map(|name| name.trim_start_matches('#'))
+        .map(|name| name.rsplit(':').next().unwrap_or(name).trim())
+        .collect();
+    (0..FIELDS.len())
+        .filter(|&i| referenced.contains(&FIELDS[i]))
+        .collect()
+}
+
+/// 写入 Anki 2.1 的集合数据库（schema 11），每条笔记生成一张新卡片
+fn write_collection(
+    conn: &Connection,
+    config: &AnkiConfig,
+    notes: &[&AnkiNote],
+) -> rusqlite::Result<()> {
+    let now_ms = now_millis();
+    let now = now_ms / 1000;
+    let deck_id = stable_id("deck", &config.deck_name);
+    let model_id = stable_id("model", &config.note_type);
+
+    conn.execute_batch(
+        "CREATE TABLE col (
+            id integer primary key, crt integer not null, mod integer not null,
+            scm integer not null, ver integer not null, dty integer not null,
+            usn integer not null, ls integer not null, conf text not null,
+            models text not null, decks text not null, dconf text not null, tags text not null
+        );
+        CREATE TABLE notes (
+            id integer primary key, guid text not null, mid integer not null,
+            mod integer not null, usn integer not null, tags text not null,
+            flds text not null, sfld integer not null, csum integer not null,
+            flags integer not null, data text not null
+        );
+        CREATE TABLE cards (
+            id integer primary key, nid integer not null, did integer not null,
+            ord integer not null, mod integer not null, usn integer not null,
+            type integer not null, queue integer not null, due integer not null,
+            ivl integer not null, factor integer not null, reps integer not null,
+            lapses integer not null, left integer not null, odue integer not null,
+            odid integer not null, flags integer not null, data text not null
+        );
+        CREATE TABLE revlog (
+            id integer primary key, cid integer not null, usn integer not null,
+            ease integer not null, ivl integer not null, lastIvl integer not null,
+            factor integer not null, time integer not null, type integer not null
+        );
+        CREATE TABLE graves (usn integer not null, oid integer not null, type integer not null);
+        CREATE INDEX ix_notes_usn ON notes (usn);
+        CREATE INDEX ix_cards_usn ON cards (usn);
+        CREATE INDEX ix_revlog_usn ON revlog (usn);
+        CREATE INDEX ix_cards_nid ON cards (nid);
+        CREATE INDEX ix_cards_sched ON cards (did, queue, due);
+        CREATE INDEX ix_revlog_cid ON revlog (cid);
+        CREATE INDEX ix_notes_csum ON notes (csum);",
+    )?;
+
+    let deck = |id: i64, name: &str| {
+        json!({
+            "id": id, "name": name, "desc": "", "mod": now, "usn": -1,
+            "collapsed": false, "browserCollapsed": false, "dyn": 0, "conf": 1,
+            "extendNew": 0, "extendRev": 0,
+            "newToday": [0, 0], "revToday": [0, 0], "lrnToday": [0, 0], "timeToday": [0, 0]
+        })
+    };
+    let decks = json!({
+        "1": deck(1, "Default"),
+        deck_id.to_string(): deck(deck_id, &config.deck_name),
+    });
+    let fields: Vec<_> = FIELDS
+        .iter()
+        .enumerate()
+        .map(|(ord, name)| {
+            json!({
+                "name": name, "ord": ord, "sticky": false, "rtl": false,
+                "font": "Arial", "size": 20, "media": []
+            })
+        })
+        .collect();
+    let models = json!({
+        model_id.to_string(): {
+            "id": model_id, "name": config.note_type, "type": 0, "mod": now, "usn": -1,
+            "sortf": 0, "did": deck_id, "flds": fields,
+            "tmpls": [{
+                "name": "Card 1", "ord": 0, "did": null, "bqfmt": "", "bafmt": "",
+                "qfmt": config.front_template, "afmt": config.back_template
+            }],
+            "css": config.css,
+            "latexPre": "\\documentclass[12pt]{article}\n\\special{papersize=3in,5in}\n\\usepackage[utf8]{inputenc}\n\\usepackage{amssymb,amsmath}\n\\pagestyle{empty}\n\\setlength{\\parindent}{0in}\n\\begin{document}\n",
+            "latexPost": "\\end{document}",
+            "latexsvg": false,
+            "req": [[0, "any", required_fields(config)]],
+            "tags": [], "vers": []
+        }
+    });
+    let dconf = json!({
+        "1": {
+            "id": 1, "name": "Default", "mod": 0, "usn": 0, "maxTaken": 60,
+            "autoplay": true, "timer": 0, "replayq": true, "dyn": false,
+            "new": {
+                "delays": [1, 10], "ints": [1, 4, 7], "initialFactor": 2500,
+                "order": 1, "perDay": 20, "bury": true, "separate": true
+            },
+            "rev": {
+                "perDay": 200, "ease4": 1.3, "fuzz": 0.05, "minSpace": 1,
+                "maxIvl": 36500, "ivlFct": 1, "bury": true, "hardFactor": 1.2
+            },
+            "lapse": {
+                "delays": [10], "mult": 0, "minInt": 1, "leechFails": 8, "leechAction": 0
+            }
+        }
+    });
+    let conf = json!({
+        "activeDecks": [deck_id], "curDeck": deck_id, "curModel": model_id,
+        "nextPos": notes.len() + 1, "sortType": "noteFld", "sortBackwards": false,
+        "newSpread": 0, "collapseTime": 1200, "timeLim": 0, "estTimes": true,
+        "dueCounts": true, "addToCur": true
+    });
+    conn.execute(
+        "INSERT INTO col VALUES (1, ?1, ?2, ?2, 11, 0, 0, 0, ?3, ?4, ?5, ?6, '{}')",
+        params![
+            now - now % 86_400,
+            now_ms,
+            conf.to_string(),
+            models.to_string(),
+            decks.to_string(),
+            dconf.to_string()
+        ],
+    )?;
+
+    for (i, note) in notes.iter().enumerate() {
+        // 笔记与卡片 ID 以毫秒时间戳为基础递增，与 Anki 自己分配的方式一致
+        let id = now_ms + i as i64;
+        let sort_field = note.sort_field();
+        conn.execute(
+            "INSERT INTO notes VALUES (?1, ?2, ?3, ?4, -1, ?5, ?6, ?7, ?8, 0, '')",
+            params![
+                id,
+                note.guid(),
+                model_id,
+                now,
+                note.tags(),
+                note.fields(),
+                sort_field,
+                checksum(&sort_field)
+            ],
+        )?;
+        conn.execute(
+            "INSERT INTO cards VALUES (?1, ?1, ?2, 0, ?3, -1, 0, 0, ?4, 0, 0, 0, 0, 0, 0, 0, 0, '')",
+            params![id, deck_id, now, i as i64 + 1],
+        )?;
+    }
+    Ok(())
+}
+
+/// 导出为 .apkg：压缩包中包含集合数据库 `collection.anki2` 与媒体清单 `media`（没有媒体文件）。
+/// 正面与语言方向相同的笔记只导出一次，返回导出的笔记数
+pub fn export(path: &Path, config: &AnkiConfig, notes: &[AnkiNote]) -> Result<usize, String> {
+    validate(config)?;
+    let mut seen = std::collections::HashSet::new();
+    let notes: Vec<&AnkiNote> = notes
+        .iter()
+        .filter(|note| !note.front.trim().is_empty() && seen.insert(note.guid()))
+        .collect();
+    if notes.is_empty() {
+        return Err("Nothing to export".to_string());
+    }
+
+    // SQLite 只能写入文件，先写到临时文件再放进压缩包
+    let collection =
+        std::env::temp_dir().join(format!("simple_translate_{}.anki2", uuid::Uuid::new_v4()));
+    let written = Connection::open(&collection)
+        .and_then(|conn| write_collection(&conn, config, &notes))
+        .map_err(|e| format!("Failed to build Anki collection: {}", e))
+        .and_then(|_| {
+            fs::read(&collection).map_err(|e| format!("Failed to read Anki collection: {}", e))
+        });
+    let _ = fs::remove_file(&collection);
+    let collection = written?;
+
+    let file =
+        fs::File::create(path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default();
+    let write = || -> zip::result::ZipResult<()> {
+        zip.start_file("collection.anki2", options)?;
+        zip.write_all(&collection)?;
+        zip.start_file("media", options)?;
+        zip.write_all(b"{}")?;
+        zip.finish()?;
+        Ok(())
+    };
+    write().map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    info!("导出 Anki 牌组: {} 条 -> {}", notes.len(), path.display());
+    Ok(notes.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::path::PathBuf;
+
+    fn note(front: &str, back: &str) -> AnkiNote {
+        AnkiNote {
+            front: front.to_string(),
+            back: back.to_string(),
+            example: None,
+            phonetic: None,
+            source_lang: "en".to_string(),
+            target_lang: "zh".to_string(),
+        }
+    }
+
+    /// 解压 .apkg 并打开其中的集合数据库，返回连接与媒体清单
+    fn open_package(path: &Path) -> (Connection, String) {
+        let mut archive = zip::ZipArchive::new(fs::File::open(path).unwrap()).unwrap();
+        let mut media = String::new();
+        archive
+            .by_name("media")
+            .unwrap()
+            .read_to_string(&mut media)
+            .unwrap();
+        let mut collection = Vec::new();
+        archive
+            .by_name("collection.anki2")
+            .unwrap()
+            .read_to_end(&mut collection)
+            .unwrap();
+        let db = path.with_extension("anki2");
+        fs::write(&db, collection).unwrap();
+        (Connection::open(&db).unwrap(), media)
+    }
+
+    /// 临时的 .apkg 路径，测试结束（包括断言失败）时删除 .apkg 与解压出的 .anki2
+    struct TempPackage(PathBuf);
+
+    impl TempPackage {
+        fn new(name: &str) -> Self {
+            Self(std::env::temp_dir().join(format!("{}_{}.apkg", name, uuid::Uuid::new_v4())))
+        }
+    }
+
+    impl Drop for TempPackage {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+            let _ = fs::remove_file(self.0.with_extension("anki2"));
+        }
+    }
+
+    #[test]
+    fn exported_collection_opens_with_notes_and_cards() {
+        let package = TempPackage::new("anki_export");
+        let path = package.0.as_path();
+        let mut apple = note("apple", "苹果");
+        apple.example = Some("An apple a day <keeps> the doctor away.".to_string());
+        apple.phonetic = Some("/ˈæp.əl/".to_string());
+        let notes = [apple, note("give up", "放弃\n停止")];
+        let config = AnkiConfig::default();
+
+        assert_eq!(export(path, &config, &notes), Ok(2));
+        let (conn, media) = open_package(path);
+        assert_eq!(media, "{}");
+
+        let integrity: String = conn
+            .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(integrity, "ok");
+
+        let (ver, models, decks): (i64, String, String) = conn
+            .query_row("SELECT ver, models, decks FROM col", [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .unwrap();
+        assert_eq!(ver, 11);
+        let models: serde_json::Value = serde_json::from_str(&models).unwrap();
+        let model_id = stable_id("model", &config.note_type);
+        let model = &models[model_id.to_string()];
+        assert_eq!(model["name"], "Simple Translate");
+        assert_eq!(model["flds"].as_array().unwrap().len(), FIELDS.len());
+        assert_eq!(model["tmpls"][0]["qfmt"], "{{Front}}");
+        assert_eq!(model["req"], json!([[0, "any", [0]]]));
+        let decks: serde_json::Value = serde_json::from_str(&decks).unwrap();
+        let deck_id = stable_id("deck", &config.deck_name);
+        assert_eq!(decks[deck_id.to_string()]["name"], "Simple Translate");
+
+        let mut stmt = conn
+            .prepare("SELECT mid, flds, sfld, csum, tags FROM notes ORDER BY id")
+            .unwrap();
+        let rows: Vec<(i64, String, String, i64, String)> = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            })
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|row| row.0 == model_id));
+        assert_eq!(
+            rows[0].1,
+            "apple\x1f苹果\x1fAn apple a day &lt;keeps&gt; the doctor away.\x1f/ˈæp.əl/"
+        );
+        assert_eq!(rows[1].1, "give up\x1f放弃<br>停止\x1f\x1f");
+        assert_eq!(rows[0].2, "apple");
+        assert_eq!(rows[0].3, checksum("apple"));
+        assert_eq!(rows[0].4, " simple_translate en-zh ");
+
+        let cards: Vec<(i64, i64, i64)> = conn
+            .prepare("SELECT did, queue, due FROM cards ORDER BY due")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(cards, vec![(deck_id, 0, 1), (deck_id, 0, 2)]);
+        let orphans: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM cards WHERE nid NOT IN (SELECT id FROM notes)",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(orphans, 0);
+    }
+
+    #[test]
+    fn duplicate_fronts_are_exported_once() {
+        let package = TempPackage::new("anki_duplicates");
+        let path = package.0.as_path();
+        let notes = [
+            note("apple", "苹果"),
+            note(" apple ", "苹果"),
+            note("", "空"),
+        ];
+        assert_eq!(export(path, &AnkiConfig::default(), &notes), Ok(1));
+        let (conn, _) = open_package(path);
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM cards", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn custom_note_type_is_used() {
+        let package = TempPackage::new("anki_custom");
+        let path = package.0.as_path();
+        let config = AnkiConfig {
+            deck_name: "Vocabulary::English".to_string(),
+            note_type: "Word".to_string(),
+            front_template: "{{Front}}<br>{{Phonetic}}".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(export(path, &config, &[note("apple", "苹果")]), Ok(1));
+        let (conn, _) = open_package(path);
+        let models: String = conn
+            .query_row("SELECT models FROM col", [], |row| row.get(0))
+            .unwrap();
+        let models: serde_json::Value = serde_json::from_str(&models).unwrap();
+        let model = &models[stable_id("model", "Word").to_string()];
+        assert_eq!(model["name"], "Word");
+        assert_eq!(model["req"], json!([[0, "any", [0, 3]]]));
+    }
+
+    #[test]
+    fn checksum_uses_stripped_first_field() {
+        let package = TempPackage::new("anki_checksum");
+        let path = package.0.as_path();
+        let notes = [note("R&D <team>", "研发团队"), note("line\nbreak", "换行")];
+        assert_eq!(export(path, &AnkiConfig::default(), &notes), Ok(2));
+        let (conn, _) = open_package(path);
+        let rows: Vec<(String, String, i64)> = conn
+            .prepare("SELECT flds, sfld, csum FROM notes ORDER BY id")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert!(rows[0].0.starts_with("R&amp;D &lt;team&gt;\x1f"));
+        assert_eq!(rows[0].1, "R&D <team>");
+        assert_eq!(rows[0].2, checksum("R&D <team>"));
+        assert!(rows[1].0.starts_with("line<br>break\x1f"));
+        assert_eq!(rows[1].1, "linebreak");
+        assert_eq!(rows[1].2, checksum("linebreak"));
+    }
+
+    #[test]
+    fn required_fields_understand_sections_and_filters() {
+        let required = |front_template: &str| {
+            required_fields(&AnkiConfig {
+                front_template: front_template.to_string(),
+                ..Default::default()
+            })
+        };
+        assert_eq!(required("{{Front}}"), [0]);
+        assert_eq!(required("{{#Example}}{{Example}}{{/Example}}"), [2]);
+        assert_eq!(required("{{text:Front}} {{ tts en_US:Phonetic }}"), [0, 3]);
+        assert_eq!(
+            required("{{^Back}}no translation{{/Back}}"),
+            Vec::<usize>::new()
+        );
+        assert_eq!(required("{{FrontSide}} {{Tags}}"), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn invalid_config_and_empty_export_are_rejected() {
+        let package = TempPackage::new("anki_invalid");
+        let path = package.0.as_path();
+        let config = AnkiConfig {
+            front_template: "no fields".to_string(),
+            ..Default::default()
+        };
+        assert!(export(path, &config, &[note("apple", "苹果")]).is_err());
+        assert_eq!(
+            export(path, &AnkiConfig::default(), &[]),
+            Err("Nothing to export".to_string())
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use log::{debug, info, warn};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+use crate::db::{now, Database};
+
+/// 翻译结果的磁盘缓存，相同文本、语言方向与服务设置直接返回上次的译文
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct CacheConfig {
+    pub enabled: bool,
+    /// 缓存有效期，0 表示永不过期
+    pub ttl_hours: u64,
+    /// 最多保留的条目数，超出时淘汰最久未使用的条目
+    pub max_entries: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl_hours: 24 * 30,
+            max_entries: 10_000,
+        }
+    }
+}
+
+/// 缓存中的一条译文
+pub struct CachedTranslation {
+    pub text: String,
+    pub detected_lang: Option<String>,
+}
+
+/// 决定缓存键的全部输入
+pub struct CacheKey<'a> {
+    pub provider: &'a str,
+    pub source_lang: &'a str,
+    pub target_lang: &'a str,
+    pub text: &'a str,
+    /// 影响译文的服务设置（模型、提示词、术语表等）序列化后的内容
+    pub options: &'a str,
+}
+
+impl CacheKey<'_> {
+    /// 各部分以 `\0` 分隔后取 SHA-256，设置中的密钥不会以明文写入缓存
+    fn digest(&self) -> String {
+        let mut hasher = Sha256::new();
+        for part in [
+            self.provider,
+            self.source_lang,
+            self.target_lang,
+            &normalize(self.text),
+            self.options,
+        ] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        hex::encode(hasher.finalize())
+    }
+}
+
+/// 统一换行符并合并行内连续空白，只因排版不同的文本共用同一条缓存
+fn normalize(text: &str) -> String {
+    text.trim()
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+static DB: Database = Database::new(
+    "Translation cache is not available",
+    "Failed to clear cache",
+);
+
+/// `accessed_at` 保存递增的访问序号而不是时间：同一秒内访问的条目按时间会并列，
+/// 淘汰哪一条就不确定了。序号从现有最大值加一，旧数据库中的秒级时间戳也能保持先后顺序
+const NEXT_ACCESS: &str = "(SELECT IFNULL(MAX(accessed_at), 0) + 1 FROM translations)";
+
+/// 打开（或创建）缓存数据库，失败时缓存不可用但不影响翻译
+pub fn init(path: &Path) {
+    let opened = DB.open(
+        path,
+        "CREATE TABLE IF NOT EXISTS translations (
+            key TEXT PRIMARY KEY,
+            provider TEXT NOT NULL,
+            source_lang TEXT NOT NULL,
+            target_lang TEXT NOT NULL,
+            translation TEXT NOT NULL,
+            detected_lang TEXT,
+            created_at INTEGER NOT NULL,
+            accessed_at INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS translations_accessed_at ON translations (accessed_at);",
+    );
+    match opened {
+        Ok(()) => info!("翻译缓存: {}", path.display()),
+        Err(e) => warn!("打开翻译缓存失败，本次运行不使用缓存: {}", e),
+    }
+}
+
+/// 查找未过期的译文，命中时刷新最近使用时间
+pub fn get(config: &CacheConfig, key: &CacheKey<'_>) -> Option<CachedTranslation> {
+    if !config.enabled {
+        return None;
+    }
+    let guard = DB.lock();
+    let conn = guard.as_ref()?;
+    let digest = key.digest();
+
+    let lookup = || -> rusqlite::Result<Option<CachedTranslation>> {
+        let row = conn
+            .query_row(
+                "SELECT translation, detected_lang, created_at
+                 FROM translations WHERE key = ?1",
+                params![digest],
+                |row| {
+                    Ok((
+                        CachedTranslation {
+                            text: row.get(0)?,
+                            detected_lang: row.get(1)?,
+                        },
+                        row.get::<_, i64>(2)?,
+                    ))
+                },
+            )
+            .optional()?;
+        let Some((cached, created_at)) = row else {
+            return Ok(None);
+        };
+
+        if config.ttl_hours > 0 && now() - created_at > config.ttl_hours as i64 * 3600 {
+            conn.execute("DELETE FROM translations WHERE key = ?1", params![digest])?;
+            debug!("缓存已过期");
+            return Ok(None);
+        }
+        conn.execute(
+            &format!(
+                "UPDATE translations SET accessed_at = {} WHERE key = ?1",
+                NEXT_ACCESS
+            ),
+            params![digest],
+        )?;
+        Ok(Some(cached))
+    };
+
+    lookup().unwrap_or_else(|e| {
+        warn!("读取翻译缓存失败: {}", e);
+        None
+    })
+}
+
+/// 写入译文，超出条目上限时淘汰最久未使用的条目
+pub fn put(config: &CacheConfig, key: &CacheKey<'_>, text: &str, detected_lang: Option<&str>) {
+    if !config.enabled {
+        return;
+    }
+    let guard = DB.lock();
+    let Some(conn) = guard.as_ref() else {
+        return;
+    };
+
+    let store = || -> rusqlite::Result<()> {
+        conn.execute(
+            &format!(
+                "INSERT OR REPLACE INTO translations
+                 (key, provider, source_lang, target_lang, translation, detected_lang, created_at, accessed_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, {})",
+                NEXT_ACCESS
+            ),
+            params![
+                key.digest(),
+                key.provider,
+                key.source_lang,
+                key.target_lang,
+                text,
+                detected_lang,
+                now()
+            ],
+        )?;
+
+        if config.max_entries > 0 {
+            let evicted = conn.execute(
+                "DELETE FROM translations WHERE key IN (
+                    SELECT key FROM translations ORDER BY accessed_at DESC LIMIT -1 OFFSET ?1
+                )",
+                params![config.max_entries as i64],
+            )?;
+            if evicted > 0 {
+                debug!("淘汰 {} 条翻译缓存", evicted);
+            }
+        }
+        Ok(())
+    };
+
+    if let Err(e) = store() {
+        warn!("写入翻译缓存失败: {}", e);
+    }
+}
+
+/// 清空缓存，返回删除的条目数
+pub fn clear() -> Result<usize, String> {
+    let removed = DB.with(|conn| {
+        let removed = conn.execute("DELETE FROM translations", [])?;
+        // 释放已删除条目占用的磁盘空间
+        let _ = conn.execute_batch("VACUUM");
+        Ok(removed)
+    })?;
+    info!("已清空翻译缓存: {} 条", removed);
+    Ok(removed)
+}
@@ -0,0 +1,53 @@
+use rusqlite::Connection;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 缓存、历史与生词本各自独占的 SQLite 数据库
+///
+/// 打开失败时连接为空，对应的功能不可用但不影响翻译
+pub(crate) struct Database {
+    conn: Mutex<Option<Connection>>,
+    /// 数据库不可用时返回的错误
+    unavailable: &'static str,
+    /// SQL 出错时错误信息的前缀
+    error_prefix: &'static str,
+}
+
+impl Database {
+    pub const fn new(unavailable: &'static str, error_prefix: &'static str) -> Self {
+        Self {
+            conn: Mutex::new(None),
+            unavailable,
+            error_prefix,
+        }
+    }
+
+    /// 打开（或创建）数据库并执行建表语句
+    pub fn open(&self, path: &Path, schema: &str) -> rusqlite::Result<()> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(schema)?;
+        *self.conn.lock().unwrap() = Some(conn);
+        Ok(())
+    }
+
+    /// 直接取得连接，数据库不可用时为 `None`，适合出错时静默跳过的调用方
+    pub fn lock(&self) -> MutexGuard<'_, Option<Connection>> {
+        self.conn.lock().unwrap()
+    }
+
+    /// 在连接上执行 `f`，数据库不可用或 SQL 出错时返回面向前端的错误
+    pub fn with<T>(&self, f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+        let guard = self.lock();
+        let conn = guard.as_ref().ok_or_else(|| self.unavailable.to_string())?;
+        f(conn).map_err(|e| format!("{}: {}", self.error_prefix, e))
+    }
+}
+
+/// 当前的 Unix 时间戳（秒）
+pub(crate) fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
@@ -0,0 +1,523 @@
+use log::{info, warn};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, Row};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::db::{now, Database};
+
+/// 翻译历史设置
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct HistoryConfig {
+    pub enabled: bool,
+    /// 最多保留的未置顶条目数，超出时删除最早的条目，0 表示不限制
+    pub max_entries: u64,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_entries: 5_000,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HistoryEntry {
+    pub id: i64,
+    pub source_text: String,
+    pub translation: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    /// 源语言为自动检测时识别出的语言
+    pub detected_lang: Option<String>,
+    pub provider: String,
+    /// 选中文本所在的应用，无法获取时为空
+    pub app: Option<String>,
+    /// Unix 时间戳（秒）
+    pub created_at: i64,
+    pub pinned: bool,
+}
+
+/// 新增的一条历史，`id` 由数据库分配
+pub struct NewEntry<'a> {
+    pub source_text: &'a str,
+    pub translation: &'a str,
+    pub source_lang: &'a str,
+    pub target_lang: &'a str,
+    pub detected_lang: Option<&'a str>,
+    pub provider: &'a str,
+    pub app: Option<&'a str>,
+    /// 为空时使用当前时间，导入时保留原来的时间
+    pub created_at: Option<i64>,
+    pub pinned: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct HistoryPage {
+    pub entries: Vec<HistoryEntry>,
+    /// 满足条件的条目总数，用于分页
+    pub total: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ImportSummary {
+    pub imported: usize,
+    /// 原文、译文与语言方向都与已有条目相同而跳过的条目数
+    pub duplicates: usize,
+}
+
+static DB: Database = Database::new(
+    "Translation history is not available",
+    "History database error",
+);
+
+/// 中日韩文字没有空格分词，按单字与相邻两字建立索引
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'   // 平假名、片假名
+        | '\u{3400}'..='\u{4DBF}' // 扩展 A
+        | '\u{4E00}'..='\u{9FFF}' // 基本汉字
+        | '\u{AC00}'..='\u{D7AF}' // 韩文音节
+        | '\u{F900}'..='\u{FAFF}' // 兼容汉字
+    )
+}
+
+/// 把文本拆成交替出现的中日韩片段与其他片段
+fn segments(text: &str) -> Vec<(bool, String)> {
+    let mut segments: Vec<(bool, String)> = Vec::new();
+    for c in text.chars() {
+        let cjk = is_cjk(c);
+        match segments.last_mut() {
+            Some((last_cjk, segment)) if *last_cjk == cjk => segment.push(c),
+            _ => segments.push((cjk, c.to_string())),
+        }
+    }
+    segments
+}
+
+fn bigrams(run: &[char]) -> impl Iterator<Item = String> + '_ {
+    run.windows(2).map(|w| w.iter().collect())
+}
+
+/// 写入全文索引的内容：中日韩片段展开为单字与双字，其余部分交给 unicode61 分词
+fn index_text(text: &str) -> String {
+    let mut tokens = Vec::new();
+    for (cjk, segment) in segments(text) {
+        if !cjk {
+            tokens.push(segment);
+            continue;
+        }
+        let run: Vec<char> = segment.chars().collect();
+        tokens.extend(run.iter().map(char::to_string));
+        tokens.extend(bigrams(&run));
+    }
+    tokens.join(" ")
+}
+
+/// 把用户输入转换为 FTS5 查询：中日韩片段按双字匹配，其余单词按前缀匹配，各部分同时满足
+fn match_query(query: &str) -> Option<String> {
+    let quote = |term: &str| format!("\"{}\"", term.replace('"', "\"\""));
+    let mut terms = Vec::new();
+    for (cjk, segment) in segments(query) {
+        if cjk {
+            let run: Vec<char> = segment.chars().collect();
+            if run.len() == 1 {
+                terms.push(quote(&segment));
+            } else {
+                terms.extend(bigrams(&run).map(|t| quote(&t)));
+            }
+        } else {
+            terms.extend(
+                segment
+                    .split(|c: char| !c.is_alphanumeric())
+                    .filter(|w| !w.is_empty())
+                    .map(|w| format!("{}*", quote(w))),
+            );
+        }
+    }
+    (!terms.is_empty()).then(|| terms.join(" AND "))
+}
+
+/// 打开（或创建）历史数据库，失败时不记录历史但不影响翻译
+pub fn init(path: &Path) {
+    let opened = DB.open(
+        path,
+        "CREATE TABLE IF NOT EXISTS history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            source_text TEXT NOT NULL,
+            translation TEXT NOT NULL,
+            source_lang TEXT NOT NULL,
+            target_lang TEXT NOT NULL,
+            detected_lang TEXT,
+            provider TEXT NOT NULL,
+            app TEXT,
+            created_at INTEGER NOT NULL,
+            pinned INTEGER NOT NULL DEFAULT 0
+        );
+        CREATE INDEX IF NOT EXISTS history_created_at ON history (created_at);
+        CREATE INDEX IF NOT EXISTS history_source_text ON history (source_text);
+        CREATE VIRTUAL TABLE IF NOT EXISTS history_fts USING fts5(source, target);",
+    );
+    match opened {
+        Ok(()) => info!("翻译历史: {}", path.display()),
+        Err(e) => warn!("打开翻译历史失败，本次运行不记录历史: {}", e),
+    }
+}
+
+fn read_entry(row: &Row<'_>) -> rusqlite::Result<HistoryEntry> {
+    Ok(HistoryEntry {
+        id: row.get("id")?,
+        source_text: row.get("source_text")?,
+        translation: row.get("translation")?,
+        source_lang: row.get("source_lang")?,
+        target_lang: row.get("target_lang")?,
+        detected_lang: row.get("detected_lang")?,
+        provider: row.get("provider")?,
+        app: row.get("app")?,
+        created_at: row.get("created_at")?,
+        pinned: row.get("pinned")?,
+    })
+}
+
+/// 插入一条历史并建立索引，返回新条目的 ID
+fn insert(conn: &Connection, entry: &NewEntry<'_>) -> rusqlite::Result<i64> {
+    conn.execute(
+        "INSERT INTO history
+         (source_text, translation, source_lang, target_lang, detected_lang, provider, app, created_at, pinned)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            entry.source_text,
+            entry.translation,
+            entry.source_lang,
+            entry.target_lang,
+            entry.detected_lang,
+            entry.provider,
+            entry.app,
+            entry.created_at.unwrap_or_else(now),
+            entry.pinned
+        ],
+    )?;
+    let id = conn.last_insert_rowid();
+    conn.execute(
+        "INSERT INTO history_fts (rowid, source, target) VALUES (?1, ?2, ?3)",
+        params![
+            id,
+            index_text(entry.source_text),
+            index_text(entry.translation)
+        ],
+    )?;
+    Ok(id)
+}
+
+fn delete_ids(conn: &Connection, ids: &[i64]) -> rusqlite::Result<usize> {
+    let mut removed = 0;
+    for id in ids {
+        removed += conn.execute("DELETE FROM history WHERE id = ?1", params![id])?;
+        conn.execute("DELETE FROM history_fts WHERE rowid = ?1", params![id])?;
+    }
+    Ok(removed)
+}
+
+/// 记录一次成功的翻译，失败只记录日志
+pub fn record(config: &HistoryConfig, entry: &NewEntry<'_>) {
+    if !config.enabled {
+        return;
+    }
+    let result = DB.with(|conn| {
+        insert(conn, entry)?;
+        if config.max_entries > 0 {
+            let mut stmt = conn.prepare(
+                "SELECT id FROM history WHERE pinned = 0
+                 ORDER BY created_at DESC, id DESC LIMIT -1 OFFSET ?1",
+            )?;
+            let expired = stmt
+                .query_map(params![config.max_entries as i64], |row| row.get(0))?
+                .collect::<rusqlite::Result<Vec<i64>>>()?;
+            delete_ids(conn, &expired)?;
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        warn!("记录翻译历史失败: {}", e);
+    }
+}
+
+/// 记录一次取自缓存的翻译：已有相同的条目时只更新它的时间，不再新增一行
+pub fn record_repeat(config: &HistoryConfig, entry: &NewEntry<'_>) {
+    if !config.enabled {
+        return;
+    }
+    let updated = DB.with(|conn| {
+        conn.execute(
+            "UPDATE history SET created_at = ?5 WHERE id = (
+                SELECT id FROM history
+                WHERE source_text = ?1 AND translation = ?2 AND source_lang = ?3 AND target_lang = ?4
+                ORDER BY created_at DESC, id DESC LIMIT 1
+             )",
+            params![
+                entry.source_text,
+                entry.translation,
+                entry.source_lang,
+                entry.target_lang,
+                now()
+            ],
+        )
+    });
+    match updated {
+        Ok(0) => record(config, entry),
+        Ok(_) => {}
+        Err(e) => warn!("记录翻译历史失败: {}", e),
+    }
+}
+
+/// 根据搜索条件生成 WHERE 子句与参数，输入无法检索时返回 `None`
+fn filter(query: Option<&str>, pinned_only: bool) -> Option<(String, Vec<Value>)> {
+    let fts = query.and_then(match_query);
+    if query.is_some_and(|q| !q.trim().is_empty()) && fts.is_none() {
+        // 只输入了标点等无法检索的内容
+        return None;
+    }
+
+    let mut filters = Vec::new();
+    let mut args: Vec<Value> = Vec::new();
+    if let Some(fts) = fts {
+        filters.push("id IN (SELECT rowid FROM history_fts WHERE history_fts MATCH ?)");
+        args.push(fts.into());
+    }
+    if pinned_only {
+        filters.push("pinned = 1");
+    }
+    let filter = if filters.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", filters.join(" AND "))
+    };
+    Some((filter, args))
+}
+
+/// 分页列出历史，置顶的条目在前；`query` 不为空时在原文与译文中全文搜索
+pub fn list(
+    query: Option<&str>,
+    pinned_only: bool,
+    offset: u64,
+    limit: u64,
+) -> Result<HistoryPage, String> {
+    let Some((filter, args)) = filter(query, pinned_only) else {
+        return Ok(HistoryPage {
+            entries: Vec::new(),
+            total: 0,
+        });
+    };
+
+    DB.with(|conn| {
+        let total: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM history {}", filter),
+            params_from_iter(args.iter()),
+            |row| row.get(0),
+        )?;
+
+        let mut page_args = args.clone();
+        page_args.push((limit.min(500) as i64).into());
+        page_args.push((offset as i64).into());
+        let mut stmt = conn.prepare(&format!(
+            "SELECT * FROM history {}
+             ORDER BY pinned DESC, created_at DESC, id DESC LIMIT ? OFFSET ?",
+            filter
+        ))?;
+        let entries = stmt
+            .query_map(params_from_iter(page_args.iter()), read_entry)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(HistoryPage {
+            entries,
+            total: total as u64,
+        })
+    })
+}
+
+/// 按时间顺序返回满足条件的全部条目，用于导出
+pub fn matching(query: Option<&str>, pinned_only: bool) -> Result<Vec<HistoryEntry>, String> {
+    let Some((filter, args)) = filter(query, pinned_only) else {
+        return Ok(Vec::new());
+    };
+    DB.with(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT * FROM history {} ORDER BY created_at, id",
+            filter
+        ))?;
+        let entries = stmt
+            .query_map(params_from_iter(args.iter()), read_entry)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(entries)
+    })
+}
+
+/// 按 ID 取出条目并按时间顺序排列，不存在的 ID 会被忽略
+pub fn entries(ids: &[i64]) -> Result<Vec<HistoryEntry>, String> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    DB.with(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT * FROM history WHERE id IN ({}) ORDER BY created_at, id",
+            vec!["?"; ids.len()].join(", ")
+        ))?;
+        let entries = stmt
+            .query_map(params_from_iter(ids), read_entry)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(entries)
+    })
+}
+
+/// 在一个事务中导入条目，跳过与已有条目（包括本次先导入的条目）重复的内容
+pub fn import(entries: &[NewEntry<'_>]) -> Result<ImportSummary, String> {
+    DB.with(|conn| {
+        let tx = conn.unchecked_transaction()?;
+        let mut summary = ImportSummary {
+            imported: 0,
+            duplicates: 0,
+        };
+        for entry in entries {
+            let exists = tx.query_row(
+                "SELECT EXISTS (SELECT 1 FROM history WHERE source_text = ?1
+                 AND translation = ?2 AND source_lang = ?3 AND target_lang = ?4)",
+                params![
+                    entry.source_text,
+                    entry.translation,
+                    entry.source_lang,
+                    entry.target_lang
+                ],
+                |row| row.get::<_, bool>(0),
+            )?;
+            if exists {
+                summary.duplicates += 1;
+            } else {
+                insert(&tx, entry)?;
+                summary.imported += 1;
+            }
+        }
+        tx.commit()?;
+        info!(
+            "导入翻译历史: {} 条，跳过重复 {} 条",
+            summary.imported, summary.duplicates
+        );
+        Ok(summary)
+    })
+}
+
+/// 删除指定的条目，返回实际删除的条目数
+pub fn delete(ids: &[i64]) -> Result<usize, String> {
+    DB.with(|conn| delete_ids(conn, ids))
+}
+
+/// 置顶或取消置顶，置顶的条目不会因数量上限被删除
+pub fn set_pinned(id: i64, pinned: bool) -> Result<(), String> {
+    let updated = DB.with(|conn| {
+        conn.execute(
+            "UPDATE history SET pinned = ?2 WHERE id = ?1",
+            params![id, pinned],
+        )
+    })?;
+    if updated == 0 {
+        return Err(format!("History entry {} not found", id));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 把每条文本写入与正式库相同的全文索引，返回匹配查询的行号
+    fn search(texts: &[&str], query: &str) -> Vec<i64> {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE VIRTUAL TABLE history_fts USING fts5(source, target);")
+            .unwrap();
+        for (id, text) in texts.iter().enumerate() {
+            conn.execute(
+                "INSERT INTO history_fts (rowid, source, target) VALUES (?1, ?2, '')",
+                params![id as i64 + 1, index_text(text)],
+            )
+            .unwrap();
+        }
+        let fts = match_query(query).unwrap();
+        let mut stmt = conn
+            .prepare("SELECT rowid FROM history_fts WHERE history_fts MATCH ?1 ORDER BY rowid")
+            .unwrap();
+        let ids = stmt
+            .query_map(params![fts], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<Vec<i64>>>()
+            .unwrap();
+        ids
+    }
+
+    #[test]
+    fn cjk_text_is_indexed_as_characters_and_bigrams() {
+        assert_eq!(index_text("翻译"), "翻 译 翻译");
+        assert_eq!(index_text("机器翻译"), "机 器 翻 译 机器 器翻 翻译");
+        assert_eq!(index_text("用 DeepL 翻译"), "用  DeepL  翻 译 翻译");
+    }
+
+    #[test]
+    fn queries_quote_terms_and_join_them() {
+        assert_eq!(match_query("翻").as_deref(), Some("\"翻\""));
+        assert_eq!(
+            match_query("机器翻译").as_deref(),
+            Some("\"机器\" AND \"器翻\" AND \"翻译\"")
+        );
+        assert_eq!(
+            match_query("deep 翻译").as_deref(),
+            Some("\"deep\"* AND \"翻译\"")
+        );
+        assert_eq!(
+            match_query("say \"hi\"").as_deref(),
+            Some("\"say\"* AND \"hi\"*")
+        );
+        assert_eq!(match_query(" ?! "), None);
+    }
+
+    #[test]
+    fn single_cjk_characters_match() {
+        let texts = ["机器翻译", "翻页", "机场"];
+        assert_eq!(search(&texts, "翻"), vec![1, 2]);
+        assert_eq!(search(&texts, "场"), vec![3]);
+    }
+
+    #[test]
+    fn multi_character_cjk_queries_match_adjacent_characters() {
+        let texts = ["机器翻译", "翻译机器", "机器人"];
+        assert_eq!(search(&texts, "机器"), vec![1, 2, 3]);
+        assert_eq!(search(&texts, "器翻"), vec![1]);
+        assert_eq!(search(&texts, "机器翻译"), vec![1]);
+        // 字都出现过但不相邻时不匹配
+        assert_eq!(search(&texts, "译机器翻"), Vec::<i64>::new());
+    }
+
+    #[test]
+    fn mixed_latin_and_cjk_text_matches_both_parts() {
+        let texts = ["用DeepL翻译文档", "DeepL API", "翻译文档"];
+        assert_eq!(search(&texts, "deepl 翻译"), vec![1]);
+        assert_eq!(search(&texts, "DeepL翻译"), vec![1]);
+        assert_eq!(search(&texts, "文档"), vec![1, 3]);
+    }
+
+    #[test]
+    fn latin_words_match_by_prefix() {
+        let texts = ["translation memory", "translate", "memorandum"];
+        assert_eq!(search(&texts, "transl"), vec![1, 2]);
+        assert_eq!(search(&texts, "memo"), vec![1, 3]);
+        assert_eq!(search(&texts, "Transl mem"), vec![1]);
+    }
+
+    #[test]
+    fn quotes_and_operators_in_queries_are_literal() {
+        let texts = ["say \"hello\" AND goodbye", "NOT here", "hello"];
+        assert_eq!(search(&texts, "\"hello\""), vec![1, 3]);
+        assert_eq!(search(&texts, "say \"hel"), vec![1]);
+        assert_eq!(search(&texts, "NOT"), vec![2]);
+        assert_eq!(search(&texts, "hello AND"), vec![1]);
+    }
+}
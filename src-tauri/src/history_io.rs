@@ -0,0 +1,686 @@
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, Utc};
+use log::info;
+use quick_xml::escape::{escape, resolve_predefined_entity};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::history::{self, HistoryEntry, ImportSummary, NewEntry};
+
+/// 历史导入导出支持的文件格式
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryFormat {
+    Csv,
+    Jsonl,
+    Markdown,
+    /// TMX 1.4 翻译记忆库，可导入 Trados、memoQ、OmegaT 等 CAT 工具
+    Tmx,
+}
+
+impl HistoryFormat {
+    fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "csv" => Some(Self::Csv),
+            "jsonl" | "ndjson" => Some(Self::Jsonl),
+            "md" | "markdown" => Some(Self::Markdown),
+            "tmx" => Some(Self::Tmx),
+            _ => None,
+        }
+    }
+
+    /// 未指定格式时按扩展名推断
+    fn resolve(format: Option<Self>, path: &Path) -> Result<Self, String> {
+        format
+            .or_else(|| Self::from_path(path))
+            .ok_or_else(|| "Unknown file format, expected .csv, .jsonl, .md or .tmx".to_string())
+    }
+}
+
+/// CSV、JSON Lines 与 Markdown 中的一行，列名与字段名相同
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct Record {
+    source_text: String,
+    translation: String,
+    source_lang: String,
+    target_lang: String,
+    detected_lang: Option<String>,
+    provider: String,
+    app: Option<String>,
+    /// 导出为 RFC 3339 格式的 UTC 时间，导入时也接受 Unix 时间戳
+    created_at: Option<String>,
+    pinned: bool,
+}
+
+const COLUMNS: [&str; 9] = [
+    "source_text",
+    "translation",
+    "source_lang",
+    "target_lang",
+    "detected_lang",
+    "provider",
+    "app",
+    "created_at",
+    "pinned",
+];
+
+const UTF8_BOM: &str = "\u{feff}";
+
+impl From<&HistoryEntry> for Record {
+    fn from(entry: &HistoryEntry) -> Self {
+        Self {
+            source_text: entry.source_text.clone(),
+            translation: entry.translation.clone(),
+            source_lang: entry.source_lang.clone(),
+            target_lang: entry.target_lang.clone(),
+            detected_lang: entry.detected_lang.clone(),
+            provider: entry.provider.clone(),
+            app: entry.app.clone(),
+            created_at: Some(utc(entry.created_at).to_rfc3339_opts(SecondsFormat::Secs, true)),
+            pinned: entry.pinned,
+        }
+    }
+}
+
+impl Record {
+    /// `index` 从 1 开始，用于错误提示
+    fn to_new(&self, index: usize) -> Result<NewEntry<'_>, String> {
+        if self.source_text.is_empty() || self.translation.is_empty() {
+            return Err(format!(
+                "Entry {}: source_text and translation are required",
+                index
+            ));
+        }
+        let created_at = match self.created_at.as_deref().filter(|v| !v.is_empty()) {
+            Some(value) => Some(
+                parse_time(value)
+                    .ok_or_else(|| format!("Entry {}: invalid created_at \"{}\"", index, value))?,
+            ),
+            None => None,
+        };
+        Ok(NewEntry {
+            source_text: &self.source_text,
+            translation: &self.translation,
+            source_lang: if self.source_lang.is_empty() {
+                "auto"
+            } else {
+                &self.source_lang
+            },
+            target_lang: &self.target_lang,
+            detected_lang: self.detected_lang.as_deref().filter(|v| !v.is_empty()),
+            provider: &self.provider,
+            app: self.app.as_deref().filter(|v| !v.is_empty()),
+            created_at,
+            pinned: self.pinned,
+        })
+    }
+}
+
+/// Unix 时间戳转换为 UTC 时间，超出范围时为 1970-01-01
+fn utc(timestamp: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(timestamp, 0).unwrap_or_default()
+}
+
+/// 解析 Unix 时间戳、RFC 3339（如 `2024-01-31T08:00:00Z`）或 TMX 的 `20240131T080000Z`
+fn parse_time(value: &str) -> Option<i64> {
+    if let Ok(timestamp) = value.parse() {
+        return Some(timestamp);
+    }
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Some(time.timestamp());
+    }
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ")
+        .ok()
+        .map(|time| time.and_utc().timestamp())
+}
+
+/// 带 BOM 写出，Excel 才能正确识别其中的中日韩文字
+fn write_csv(entries: &[HistoryEntry]) -> Result<String, String> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(UTF8_BOM.as_bytes().to_vec());
+    let write = |writer: &mut csv::Writer<Vec<u8>>| -> csv::Result<()> {
+        writer.write_record(COLUMNS)?;
+        for entry in entries {
+            writer.serialize(Record::from(entry))?;
+        }
+        writer.flush()?;
+        Ok(())
+    };
+    write(&mut writer).map_err(|e| format!("Failed to write CSV: {}", e))?;
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| format!("Failed to write CSV: {}", e))?;
+    String::from_utf8(bytes).map_err(|e| format!("Failed to write CSV: {}", e))
+}
+
+fn read_csv(text: &str) -> Result<Vec<Record>, String> {
+    csv::Reader::from_reader(text.as_bytes())
+        .deserialize()
+        .collect::<csv::Result<Vec<Record>>>()
+        .map_err(|e| format!("Invalid CSV: {}", e))
+}
+
+fn write_jsonl(entries: &[HistoryEntry]) -> Result<String, String> {
+    let mut out = String::new();
+    for entry in entries {
+        let line = serde_json::to_string(&Record::from(entry))
+            .map_err(|e| format!("Failed to write JSON: {}", e))?;
+        out.push_str(&line);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn read_jsonl(text: &str) -> Result<Vec<Record>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| format!("Invalid JSON on line {}: {}", i + 1, e))
+        })
+        .collect()
+}
+
+/// 单元格内的 `\` 与 `|` 用反斜杠转义，换行写成 `<br>`，`&` 与 `<` 写成实体以免与 `<br>` 混淆
+fn escape_cell(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '|' => out.push_str("\\|"),
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '\n' => out.push_str("<br>"),
+            '\r' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn unescape_cell(cell: &str) -> String {
+    cell.trim()
+        .replace("<br>", "\n")
+        .replace("&lt;", "<")
+        .replace("&amp;", "&")
+}
+
+/// 按未转义的 `|` 拆分表格行，去掉行首行尾竖线外侧的空内容
+fn split_row(line: &str) -> Vec<String> {
+    let mut cells = vec![String::new()];
+    let mut chars = line.trim().chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => cells.last_mut().unwrap().extend(chars.next()),
+            '|' => cells.push(String::new()),
+            _ => cells.last_mut().unwrap().push(c),
+        }
+    }
+    if line.trim().starts_with('|') {
+        cells.remove(0);
+    }
+    if cells.len() > 1 && cells.last().is_some_and(|c| c.trim().is_empty()) {
+        cells.pop();
+    }
+    cells.iter().map(|c| unescape_cell(c)).collect()
+}
+
+fn write_markdown(entries: &[HistoryEntry]) -> Result<String, String> {
+    let row = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+    let mut out = row(COLUMNS.iter().map(|c| c.to_string()).collect());
+    out.push_str(&row(vec!["---".to_string(); COLUMNS.len()]));
+    for entry in entries {
+        let record = Record::from(entry);
+        out.push_str(&row(vec![
+            escape_cell(&record.source_text),
+            escape_cell(&record.translation),
+            escape_cell(&record.source_lang),
+            escape_cell(&record.target_lang),
+            escape_cell(record.detected_lang.as_deref().unwrap_or_default()),
+            escape_cell(&record.provider),
+            escape_cell(record.app.as_deref().unwrap_or_default()),
+            record.created_at.unwrap_or_default(),
+            record.pinned.to_string(),
+        ]));
+    }
+    Ok(out)
+}
+
+/// 读取文件中的第一个表格，按表头匹配列，缺少的列使用默认值
+fn read_markdown(text: &str) -> Result<Vec<Record>, String> {
+    let mut lines = text
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| !line.starts_with('|'))
+        .take_while(|line| line.starts_with('|'));
+    let header = lines
+        .next()
+        .ok_or_else(|| "No table found in Markdown file".to_string())?;
+    let columns = split_row(header);
+    let is_separator = |line: &str| {
+        line.chars()
+            .all(|c| matches!(c, '|' | '-' | ':' | ' ' | '\t'))
+    };
+    if !lines.next().is_some_and(is_separator) {
+        return Err("Invalid Markdown table: missing separator row".to_string());
+    }
+
+    Ok(lines
+        .map(|line| {
+            let cells = split_row(line);
+            let cell = |name: &str| {
+                columns
+                    .iter()
+                    .position(|c| c == name)
+                    .and_then(|i| cells.get(i))
+                    .cloned()
+                    .unwrap_or_default()
+            };
+            let optional = |name: &str| Some(cell(name)).filter(|v| !v.is_empty());
+            Record {
+                source_text: cell("source_text"),
+                translation: cell("translation"),
+                source_lang: cell("source_lang"),
+                target_lang: cell("target_lang"),
+                detected_lang: optional("detected_lang"),
+                provider: cell("provider"),
+                app: optional("app"),
+                created_at: optional("created_at"),
+                pinned: matches!(cell("pinned").as_str(), "true" | "1" | "yes"),
+            }
+        })
+        .collect())
+}
+
+/// 内容为原始格式代码而不是译文的内联标记
+const INLINE_TAGS: [&[u8]; 5] = [b"bpt", b"ept", b"ph", b"it", b"ut"];
+
+/// 源语言为自动检测时，`xml:lang` 使用检测出的语言
+fn tmx_source_lang(entry: &HistoryEntry) -> &str {
+    entry.detected_lang.as_deref().unwrap_or(&entry.source_lang)
+}
+
+/// 写出 TMX 1.4，服务、应用等信息以 `x-` 开头的 `prop` 保存，导入时据此还原
+fn write_tmx(entries: &[HistoryEntry]) -> Result<String, String> {
+    let srclang = match entries.first().map(tmx_source_lang) {
+        Some(first) if entries.iter().all(|e| tmx_source_lang(e) == first) => first,
+        _ => "*all*",
+    };
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<!DOCTYPE tmx SYSTEM \"tmx14.dtd\">\n<tmx version=\"1.4\">\n");
+    out.push_str(&format!(
+        "  <header creationtool=\"simple_translate\" creationtoolversion=\"{}\" \
+         segtype=\"sentence\" o-tmf=\"simple_translate\" adminlang=\"en\" \
+         srclang=\"{}\" datatype=\"plaintext\"/>\n  <body>\n",
+        env!("CARGO_PKG_VERSION"),
+        escape(srclang)
+    ));
+
+    for entry in entries {
+        let source_lang = tmx_source_lang(entry);
+        out.push_str(&format!(
+            "    <tu creationdate=\"{}\">\n",
+            utc(entry.created_at).format("%Y%m%dT%H%M%SZ")
+        ));
+        let mut prop = |name: &str, value: &str| {
+            out.push_str(&format!(
+                "      <prop type=\"x-{}\">{}</prop>\n",
+                name,
+                escape(value)
+            ));
+        };
+        if source_lang != entry.source_lang {
+            prop("source-lang", &entry.source_lang);
+        }
+        if !entry.provider.is_empty() {
+            prop("provider", &entry.provider);
+        }
+        if let Some(app) = &entry.app {
+            prop("app", app);
+        }
+        if entry.pinned {
+            prop("pinned", "true");
+        }
+        for (lang, text) in [
+            (source_lang, &entry.source_text),
+            (entry.target_lang.as_str(), &entry.translation),
+        ] {
+            out.push_str(&format!(
+                "      <tuv xml:lang=\"{}\"><seg>{}</seg></tuv>\n",
+                escape(lang),
+                escape(text.as_str())
+            ));
+        }
+        out.push_str("    </tu>\n");
+    }
+    out.push_str("  </body>\n</tmx>\n");
+    Ok(out)
+}
+
+#[derive(Default)]
+struct TranslationUnit {
+    creationdate: Option<String>,
+    props: Vec<(String, String)>,
+    /// 语言与句段文本
+    variants: Vec<(String, String)>,
+}
+
+impl TranslationUnit {
+    fn prop(&self, name: &str) -> Option<&str> {
+        self.props
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// 源语言取 `srclang` 对应的变体，`*all*` 或找不到时取第一个，目标语言取其后的第一个其他变体
+    fn into_record(self, srclang: &str) -> Option<Record> {
+        let source = self
+            .variants
+            .iter()
+            .position(|(lang, _)| lang.eq_ignore_ascii_case(srclang))
+            .unwrap_or(0);
+        let target = (0..self.variants.len()).find(|&i| i != source)?;
+        let (lang, source_text) = &self.variants[source];
+        let (target_lang, translation) = &self.variants[target];
+        let source_lang = self.prop("x-source-lang").unwrap_or(lang);
+        Some(Record {
+            source_text: source_text.clone(),
+            translation: translation.clone(),
+            source_lang: source_lang.to_string(),
+            target_lang: target_lang.clone(),
+            detected_lang: (source_lang != lang).then(|| lang.clone()),
+            provider: self.prop("x-provider").unwrap_or_default().to_string(),
+            app: self.prop("x-app").map(str::to_string),
+            created_at: self.creationdate.clone(),
+            pinned: self.prop("x-pinned") == Some("true"),
+        })
+    }
+}
+
+fn attribute(element: &BytesStart<'_>, names: &[&str]) -> Option<String> {
+    names.iter().find_map(|name| {
+        element
+            .try_get_attribute(name)
+            .ok()
+            .flatten()
+            .and_then(|attr| attr.unescape_value().ok())
+            .map(|value| value.into_owned())
+    })
+}
+
+/// 读取 TMX，只包含一种语言的翻译单元会被忽略；`bpt`、`ph` 等内联标记中的原始代码不计入文本
+fn read_tmx(text: &str) -> Result<Vec<Record>, String> {
+    let mut reader = Reader::from_str(text);
+    let mut records = Vec::new();
+    let mut srclang = String::from("*all*");
+    let mut unit: Option<TranslationUnit> = None;
+    let mut prop: Option<(String, String)> = None;
+    let mut lang = String::new();
+    let mut seg: Option<String> = None;
+    let mut inline_depth = 0usize;
+
+    let error = |reader: &Reader<&[u8]>, e: &dyn std::fmt::Display| {
+        format!("Invalid TMX at byte {}: {}", reader.error_position(), e)
+    };
+    loop {
+        let event = reader.read_event().map_err(|e| error(&reader, &e))?;
+        let text = match &event {
+            Event::Text(t) => Some(t.xml_content().map_err(|e| error(&reader, &e))?),
+            Event::CData(t) => Some(t.xml_content().map_err(|e| error(&reader, &e))?),
+            Event::GeneralRef(r) => match r.resolve_char_ref().map_err(|e| error(&reader, &e))? {
+                Some(c) => Some(c.to_string().into()),
+                None => {
+                    let name = r.decode().map_err(|e| error(&reader, &e))?;
+                    Some(match resolve_predefined_entity(&name) {
+                        Some(value) => value.into(),
+                        None => format!("&{};", name).into(),
+                    })
+                }
+            },
+            _ => None,
+        };
+        if let Some(text) = text {
+            // 句段（内联标记之外）或属性值中的文本
+            match (&mut seg, &mut prop) {
+                (Some(seg), _) if inline_depth == 0 => seg.push_str(&text),
+                (None, Some((_, value))) => value.push_str(&text),
+                _ => {}
+            }
+            continue;
+        }
+
+        match event {
+            Event::Start(e) if seg.is_some() && INLINE_TAGS.contains(&e.name().as_ref()) => {
+                inline_depth += 1;
+            }
+            Event::End(e) if seg.is_some() && INLINE_TAGS.contains(&e.name().as_ref()) => {
+                inline_depth = inline_depth.saturating_sub(1);
+            }
+            Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"header" => {
+                if let Some(value) = attribute(&e, &["srclang"]) {
+                    srclang = value;
+                }
+            }
+            Event::Start(e) => match e.name().as_ref() {
+                b"tu" => {
+                    unit = Some(TranslationUnit {
+                        creationdate: attribute(&e, &["creationdate"]),
+                        ..Default::default()
+                    })
+                }
+                b"prop" => {
+                    prop = Some((attribute(&e, &["type"]).unwrap_or_default(), String::new()))
+                }
+                // TMX 1.1 使用 `lang`
+                b"tuv" => lang = attribute(&e, &["xml:lang", "lang"]).unwrap_or_default(),
+                b"seg" => seg = Some(String::new()),
+                _ => {}
+            },
+            Event::End(e) => match e.name().as_ref() {
+                b"seg" => {
+                    if let (Some(unit), Some(seg)) = (unit.as_mut(), seg.take()) {
+                        unit.variants.push((std::mem::take(&mut lang), seg));
+                    }
+                    inline_depth = 0;
+                }
+                b"prop" => {
+                    if let (Some(unit), Some(prop)) = (unit.as_mut(), prop.take()) {
+                        unit.props.push(prop);
+                    }
+                }
+                b"tu" => {
+                    if let Some(record) = unit.take().and_then(|u| u.into_record(&srclang)) {
+                        records.push(record);
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(records)
+}
+
+/// 导出满足搜索条件的历史，返回导出的条目数
+pub fn export(
+    path: &Path,
+    format: Option<HistoryFormat>,
+    query: Option<&str>,
+    pinned_only: bool,
+) -> Result<usize, String> {
+    let format = HistoryFormat::resolve(format, path)?;
+    let entries = history::matching(query, pinned_only)?;
+    let content = match format {
+        HistoryFormat::Csv => write_csv(&entries)?,
+        HistoryFormat::Jsonl => write_jsonl(&entries)?,
+        HistoryFormat::Markdown => write_markdown(&entries)?,
+        HistoryFormat::Tmx => write_tmx(&entries)?,
+    };
+    fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    info!("导出翻译历史: {} 条 -> {}", entries.len(), path.display());
+    Ok(entries.len())
+}
+
+/// 导入之前导出的文件，整个文件校验通过后才写入，重复的条目会被跳过
+pub fn import(path: &Path, format: Option<HistoryFormat>) -> Result<ImportSummary, String> {
+    let format = HistoryFormat::resolve(format, path)?;
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let text = text.strip_prefix(UTF8_BOM).unwrap_or(&text);
+    let records = match format {
+        HistoryFormat::Csv => read_csv(text)?,
+        HistoryFormat::Jsonl => read_jsonl(text)?,
+        HistoryFormat::Markdown => read_markdown(text)?,
+        HistoryFormat::Tmx => read_tmx(text)?,
+    };
+    let entries = records
+        .iter()
+        .enumerate()
+        .map(|(i, record)| record.to_new(i + 1))
+        .collect::<Result<Vec<_>, _>>()?;
+    history::import(&entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Key = (
+        String,
+        String,
+        String,
+        String,
+        Option<String>,
+        String,
+        Option<String>,
+        Option<i64>,
+        bool,
+    );
+
+    fn entry(id: i64, source_text: &str, translation: &str) -> HistoryEntry {
+        HistoryEntry {
+            id,
+            source_text: source_text.to_string(),
+            translation: translation.to_string(),
+            source_lang: "auto".to_string(),
+            target_lang: "zh".to_string(),
+            detected_lang: Some("en".to_string()),
+            provider: "deeplx".to_string(),
+            app: Some("Notes | Safari".to_string()),
+            created_at: 1706688000 + id,
+            pinned: true,
+        }
+    }
+
+    fn entries() -> Vec<HistoryEntry> {
+        let literal = entry(
+            1,
+            "a | b \\ c\nnext &lt; line <br> & <b>bold</b>",
+            "甲 | 乙 \\\\ 丙\n第二行 &amp; <br>",
+        );
+        let explicit = HistoryEntry {
+            source_lang: "ja".to_string(),
+            target_lang: "en".to_string(),
+            detected_lang: None,
+            provider: "deepl".to_string(),
+            app: None,
+            pinned: false,
+            ..entry(2, "翻訳\\|エラー", "Translation \"error\"")
+        };
+        vec![literal, explicit]
+    }
+
+    fn entry_key(entry: &HistoryEntry) -> Key {
+        (
+            entry.source_text.clone(),
+            entry.translation.clone(),
+            entry.source_lang.clone(),
+            entry.target_lang.clone(),
+            entry.detected_lang.clone(),
+            entry.provider.clone(),
+            entry.app.clone(),
+            Some(entry.created_at),
+            entry.pinned,
+        )
+    }
+
+    fn record_key(record: &Record) -> Key {
+        let new = record.to_new(1).unwrap();
+        (
+            new.source_text.to_string(),
+            new.translation.to_string(),
+            new.source_lang.to_string(),
+            new.target_lang.to_string(),
+            new.detected_lang.map(str::to_string),
+            new.provider.to_string(),
+            new.app.map(str::to_string),
+            new.created_at,
+            new.pinned,
+        )
+    }
+
+    fn assert_round_trip(
+        write: fn(&[HistoryEntry]) -> Result<String, String>,
+        read: fn(&str) -> Result<Vec<Record>, String>,
+    ) {
+        let entries = entries();
+        let text = write(&entries).unwrap();
+        let text = text.strip_prefix(UTF8_BOM).unwrap_or(&text);
+        let records = read(text).unwrap();
+        assert_eq!(
+            records.iter().map(record_key).collect::<Vec<_>>(),
+            entries.iter().map(entry_key).collect::<Vec<_>>(),
+            "{}",
+            text
+        );
+    }
+
+    #[test]
+    fn csv_round_trips() {
+        assert_round_trip(write_csv, read_csv);
+    }
+
+    #[test]
+    fn jsonl_round_trips() {
+        assert_round_trip(write_jsonl, read_jsonl);
+    }
+
+    #[test]
+    fn markdown_round_trips() {
+        assert_round_trip(write_markdown, read_markdown);
+    }
+
+    #[test]
+    fn tmx_round_trips() {
+        assert_round_trip(write_tmx, read_tmx);
+    }
+
+    #[test]
+    fn tmx_writes_detected_language_and_keeps_auto_as_prop() {
+        let text = write_tmx(&entries()[..1]).unwrap();
+        assert!(text.contains("srclang=\"en\""));
+        assert!(text.contains("<prop type=\"x-source-lang\">auto</prop>"));
+        assert!(text.contains("<tuv xml:lang=\"en\"><seg>a | b"));
+    }
+
+    #[test]
+    fn tmx_from_cat_tools_skips_inline_codes() {
+        let text = r#"<?xml version="1.0"?><tmx version="1.4"><header srclang="en-US"/><body>
+<tu><tuv xml:lang="de-DE"><seg>Hallo</seg></tuv><tuv xml:lang="en-US"><seg>Hello <bpt i="1">&lt;b&gt;</bpt>bold<ept i="1">&lt;/b&gt;</ept> &amp; &#x4E16;<ph>{1}</ph><![CDATA[ <raw>]]></seg></tuv></tu>
+<tu><tuv xml:lang="en-US"><seg>only one</seg></tuv></tu></body></tmx>"#;
+        let records = read_tmx(text).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].source_text, "Hello bold & 世 <raw>");
+        assert_eq!(records[0].source_lang, "en-US");
+        assert_eq!(records[0].translation, "Hallo");
+        assert_eq!(records[0].target_lang, "de-DE");
+        assert_eq!(records[0].detected_lang, None);
+    }
+}
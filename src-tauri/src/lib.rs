@@ -1,6 +1,6 @@
 use tauri::{
     AppHandle, Emitter, Manager,
-    menu::{Menu, MenuItem},
+    menu::{Menu, MenuItem, PredefinedMenuItem},
     tray::TrayIconBuilder,
 };
 use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut};
@@ -9,6 +9,7 @@ use tauri_plugin_autostart::ManagerExt;
 use enigo::{Enigo, Key, Keyboard, Settings};
 use arboard::Clipboard;
 use serde::{Deserialize, Serialize};
+use std::future::Future;
 use std::thread;
 use std::time::{Duration, Instant};
 use std::sync::{Arc, Mutex};
@@ -18,33 +19,86 @@ use log::{info, warn, error, debug};
 #[cfg(not(target_os = "macos"))]
 use rdev::{listen, EventType, Button};
 
+mod anki;
+mod cache;
+mod db;
+mod history;
+mod history_io;
+mod providers;
+mod vocabulary;
+
+use providers::{
+    build_provider, is_single_word, Capabilities, ChunkSink, DictionaryEntry, Language,
+    TranslateRequest, Translation, Usage, DEFAULT_PROVIDER,
+};
+use anki::{AnkiConfig, AnkiNote};
+use cache::{CacheConfig, CacheKey};
+use history::{HistoryConfig, HistoryPage, ImportSummary};
+use history_io::HistoryFormat;
+use vocabulary::VocabularyCard;
+use providers::aws::AwsConfig;
+use providers::azure::AzureConfig;
+use providers::baidu::BaiduConfig;
+use providers::command::CommandConfig;
+use providers::custom::CustomConfig;
+use providers::deepl::DeepLConfig;
+use providers::deeplx::DeepLxConfig;
+use providers::google::GoogleConfig;
+use providers::guard::ThrottleConfig;
+use providers::http::NetworkConfig;
+use providers::libretranslate::LibreTranslateConfig;
+use providers::offline::{InstalledModel, OfflineConfig};
+use providers::ollama::{Ollama, OllamaConfig, OllamaModel};
+use providers::retry::RetryConfig;
+use providers::openai::OpenAiConfig;
+use providers::tencent::TencentConfig;
+use providers::youdao::YoudaoConfig;
+
 static LAST_TRIGGER: Mutex<Option<Instant>> = Mutex::new(None);
 static LAST_CLICK_POS: Mutex<(i32, i32)> = Mutex::new((0, 0));
+/// 正在进行的翻译请求，新的翻译开始时中止旧的，避免过期结果覆盖弹窗
+static IN_FLIGHT: Mutex<Option<(String, tokio::task::AbortHandle)>> = Mutex::new(None);
+/// 最近一次快捷键翻译的请求 ID 与选中文本所在的应用，写入翻译历史时使用
+static SOURCE_APP: Mutex<Option<(String, String)>> = Mutex::new(None);
+/// 托盘菜单中显示今日待复习生词数的菜单项
+static REVIEW_MENU_ITEM: once_cell::sync::OnceCell<MenuItem<tauri::Wry>> = once_cell::sync::OnceCell::new();
+/// 生词到期不会触发任何事件，定时刷新托盘中的待复习数
+const REVIEW_MENU_REFRESH: Duration = Duration::from_secs(10 * 60);
 const DEFAULT_AUTO_CLOSE_TIMEOUT: u64 = 1500;
 const ALLOWED_AUTO_CLOSE_TIMEOUTS: [u64; 5] = [0, 1000, 1500, 2000, 3000];
 
-#[derive(Serialize, Deserialize)]
-struct TranslateRequest {
-    text: String,
-    source_lang: String,
-    target_lang: String,
-}
-
-#[derive(Serialize, Deserialize)]
-struct TranslateResponse {
-    code: i32,
-    data: Option<String>,
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum TranslateStatus {
+    Success,
+    Error,
+    /// 被新的翻译请求取消，前端应忽略该结果
+    Cancelled,
 }
 
 #[derive(Clone, Serialize)]
 struct TranslateResult {
+    status: TranslateStatus,
     success: bool,
     text: String,
     error: Option<String>,
+    /// 源语言为自动检测时识别出的语言，供弹窗显示
+    detected_lang: Option<String>,
+    /// 实际给出结果的服务，启用备用服务时可能与设置中的不同
+    provider: Option<String>,
+    /// 选中单个单词且服务支持词典时的其他译法
+    alternatives: Vec<DictionaryEntry>,
+    /// 包含重试与备用服务在内的请求次数
+    attempts: u32,
+    /// 最后一次失败的原因，重试后成功时同样返回
+    last_error: Option<String>,
+    /// 结果来自本地缓存，没有请求翻译服务
+    cached: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
-struct AppSettings {
+pub(crate) struct AppSettings {
+    provider: String,
     api_key: String,
     auto_close_enabled: bool,
     auto_close_timeout: u64,
@@ -53,11 +107,42 @@ struct AppSettings {
     first_run: bool,
     shortcut: String,
     auto_start: bool,
+    deeplx: DeepLxConfig,
+    deepl: DeepLConfig,
+    openai: OpenAiConfig,
+    libretranslate: LibreTranslateConfig,
+    ollama: OllamaConfig,
+    offline: OfflineConfig,
+    google: GoogleConfig,
+    azure: AzureConfig,
+    baidu: BaiduConfig,
+    youdao: YoudaoConfig,
+    tencent: TencentConfig,
+    aws: AwsConfig,
+    custom: CustomConfig,
+    command: CommandConfig,
+    /// 代理、超时与证书，所有服务共用
+    network: NetworkConfig,
+    /// 单个服务失败时的重试策略
+    retry: RetryConfig,
+    /// 每个服务的限流与熔断
+    throttle: ThrottleConfig,
+    /// 翻译结果的磁盘缓存
+    cache: CacheConfig,
+    /// 翻译历史
+    history: HistoryConfig,
+    /// 导出 Anki 牌组时的牌组名与笔记类型
+    anki: AnkiConfig,
+    /// 当前服务失败时依次尝试的备用服务
+    fallback_providers: Vec<String>,
+    /// 对比模式中同时请求的服务，为空时使用当前服务与备用服务
+    compare_providers: Vec<String>,
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
+            provider: DEFAULT_PROVIDER.to_string(),
             api_key: String::new(),
             auto_close_enabled: true,
             auto_close_timeout: DEFAULT_AUTO_CLOSE_TIMEOUT,
@@ -66,6 +151,28 @@ impl Default for AppSettings {
             first_run: true,
             shortcut: "Ctrl+Q".to_string(),
             auto_start: false,
+            deeplx: DeepLxConfig::default(),
+            deepl: DeepLConfig::default(),
+            openai: OpenAiConfig::default(),
+            libretranslate: LibreTranslateConfig::default(),
+            ollama: OllamaConfig::default(),
+            offline: OfflineConfig::default(),
+            google: GoogleConfig::default(),
+            azure: AzureConfig::default(),
+            baidu: BaiduConfig::default(),
+            youdao: YoudaoConfig::default(),
+            tencent: TencentConfig::default(),
+            aws: AwsConfig::default(),
+            custom: CustomConfig::default(),
+            command: CommandConfig::default(),
+            network: NetworkConfig::default(),
+            retry: RetryConfig::default(),
+            throttle: ThrottleConfig::default(),
+            cache: CacheConfig::default(),
+            history: HistoryConfig::default(),
+            anki: AnkiConfig::default(),
+            fallback_providers: Vec::new(),
+            compare_providers: Vec::new(),
         }
     }
 }
@@ -226,101 +333,433 @@ fn get_selected_text() -> Option<String> {
     }
 }
 
-#[tauri::command]
-async fn translate(text: String) -> TranslateResult {
-    info!("开始翻译, 文本长度: {} 字符", text.len());
-    debug!("翻译文本: {}", text);
+/// 获取前台应用的名称，即选中文本所在的应用，获取不到时返回 `None`
+///
+/// macOS 使用 lsappinfo，Linux 通过 xprop 读取 X11 活动窗口所属进程；
+/// Windows 与纯 Wayland 会话暂不支持
+fn foreground_app() -> Option<String> {
+    let app = foreground_app_name().filter(|name| !name.is_empty());
+    debug!("前台应用: {:?}", app);
+    app
+}
 
-    // Get settings from cache
-    let settings = SETTINGS_CACHE.read().await.clone();
+#[cfg(target_os = "macos")]
+fn foreground_app_name() -> Option<String> {
+    let asn = command_output("lsappinfo", &["front"])?;
+    // 输出形如 "LSDisplayName"="Safari"
+    let info = command_output("lsappinfo", &["info", "-only", "name", asn.trim()])?;
+    let name = info.split_once('=')?.1;
+    Some(name.trim().trim_matches('"').to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn foreground_app_name() -> Option<String> {
+    // 输出形如 _NET_ACTIVE_WINDOW(WINDOW): window id # 0x3a00007
+    let active = command_output("xprop", &["-root", "_NET_ACTIVE_WINDOW"])?;
+    let window = active.rsplit(' ').next()?.trim().to_string();
+    // 输出形如 _NET_WM_PID(CARDINAL) = 12345
+    let pid = command_output("xprop", &["-id", &window, "_NET_WM_PID"])?;
+    let pid: u32 = pid.split_once('=')?.1.trim().parse().ok()?;
+    let name = std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+    Some(name.trim().to_string())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn foreground_app_name() -> Option<String> {
+    None
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
 
-    if settings.api_key.is_empty() {
-        warn!("API Key is not configured");
-        return TranslateResult {
+/// 取出快捷键翻译时记录的来源应用，`request_id` 不是那次翻译时返回 `None`
+fn source_app_for(request_id: &str) -> Option<String> {
+    SOURCE_APP
+        .lock()
+        .unwrap()
+        .as_ref()
+        .filter(|(id, _)| id == request_id)
+        .map(|(_, app)| app.clone())
+}
+
+impl TranslateResult {
+    fn failed(error: String) -> Self {
+        Self {
+            status: TranslateStatus::Error,
             success: false,
             text: String::new(),
-            error: Some("API Key未配置，请在系统托盘菜单中打开设置".to_string()),
-        };
+            error: Some(error),
+            detected_lang: None,
+            provider: None,
+            alternatives: Vec::new(),
+            attempts: 0,
+            last_error: None,
+            cached: false,
+        }
     }
 
-    let client = match reqwest::Client::builder()
-        .timeout(Duration::from_secs(10))
-        .build()
-    {
-        Ok(c) => c,
-        Err(e) => {
-            error!("创建 HTTP 客户端失败: {}", e);
-            return TranslateResult {
-                success: false,
-                text: String::new(),
-                error: Some(format!("HTTP 客户端初始化失败: {}", e)),
-            };
+    fn cancelled() -> Self {
+        Self {
+            status: TranslateStatus::Cancelled,
+            ..Self::failed("翻译已取消".to_string())
         }
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct TranslateChunkPayload {
+    request_id: String,
+    delta: String,
+}
+
+#[derive(Clone, Serialize)]
+struct TranslateDonePayload {
+    request_id: String,
+    result: TranslateResult,
+}
+
+/// 缓存键中的服务设置部分，换了模型或提示词等设置后不会命中旧的译文
+fn cache_options(settings: &AppSettings, provider: &str) -> String {
+    serde_json::to_value(settings)
+        .ok()
+        .and_then(|v| v.get(provider).map(|options| options.to_string()))
+        .unwrap_or_default()
+}
+
+fn cache_key<'a>(provider: &'a str, req: &'a TranslateRequest, options: &'a str) -> CacheKey<'a> {
+    CacheKey {
+        provider,
+        source_lang: &req.source_lang,
+        target_lang: &req.target_lang,
+        text: &req.text,
+        options,
+    }
+}
+
+/// 把成功的翻译写入历史，`app` 为选中文本所在的应用；
+/// 命中缓存时只更新已有条目的时间，重复查询同一段文本不会产生多条历史
+fn record_history(settings: &AppSettings, req: &TranslateRequest, result: &TranslateResult, app: Option<&str>) {
+    let entry = history::NewEntry {
+        source_text: &req.text,
+        translation: &result.text,
+        source_lang: &req.source_lang,
+        target_lang: &req.target_lang,
+        detected_lang: result.detected_lang.as_deref(),
+        provider: result.provider.as_deref().unwrap_or_default(),
+        app,
+        created_at: None,
+        pinned: false,
     };
+    if result.cached {
+        history::record_repeat(&settings.history, &entry);
+    } else {
+        history::record(&settings.history, &entry);
+    }
+}
+
+/// 使用当前设置翻译文本，`on_chunk` 不为空时走流式接口
+///
+/// 先查找缓存，`bypass_cache` 为 true 时跳过缓存直接请求并用新结果覆盖；
+/// 当前服务失败时按设置中的备用服务依次重试；`app` 为选中文本所在的应用，随结果写入历史
+async fn run_translation(
+    text: String,
+    on_chunk: Option<ChunkSink<'_>>,
+    bypass_cache: bool,
+    app: Option<String>,
+) -> TranslateResult {
+    // Get settings from cache
+    let settings = SETTINGS_CACHE.read().await.clone();
 
     let req = TranslateRequest {
-        text: text.clone(),
+        text,
         source_lang: settings.source_lang.clone(),
         target_lang: settings.target_lang.clone(),
     };
 
-    let url = format!("https://api.deeplx.org/{}/translate", settings.api_key);
-    info!("请求 API: {}", url);
+    // 翻译链轮到哪个服务才查找它的缓存，不会越过当前服务使用备用服务的缓存
+    let lookup = |provider: &str| {
+        let options = cache_options(&settings, provider);
+        cache::get(&settings.cache, &cache_key(provider, &req, &options)).map(|cached| Translation {
+            text: cached.text,
+            detected_source_lang: cached.detected_lang,
+        })
+    };
+    let cached: Option<providers::fallback::CacheLookup<'_>> = if bypass_cache { None } else { Some(&lookup) };
 
-    match client
-        .post(&url)
-        .json(&req)
-        .send()
-        .await
+    let chain = providers::fallback::translate(&settings, &req, on_chunk, cached).await;
+    let (provider, outcome) = (chain.provider, chain.outcome);
+    let attempts = outcome.attempts;
+    let last_error = outcome.last_error.map(|e| e.to_string());
+
+    match outcome.result {
+        Ok(translation) => {
+            let alternatives = if chain.cached {
+                Vec::new()
+            } else {
+                info!("翻译成功 ({}), 结果长度: {} 字符, 请求 {} 次", provider, translation.text.len(), attempts);
+                debug!("翻译结果: {}", translation.text);
+                let options = cache_options(&settings, &provider);
+                cache::put(
+                    &settings.cache,
+                    &cache_key(&provider, &req, &options),
+                    &translation.text,
+                    translation.detected_source_lang.as_deref(),
+                );
+                lookup_alternatives(&settings, &provider, &req, translation.detected_source_lang.as_deref()).await
+            };
+            let result = TranslateResult {
+                status: TranslateStatus::Success,
+                success: true,
+                text: translation.text,
+                error: None,
+                detected_lang: translation.detected_source_lang,
+                provider: Some(provider),
+                alternatives,
+                attempts,
+                last_error,
+                cached: chain.cached,
+            };
+            record_history(&settings, &req, &result, app.as_deref());
+            result
+        }
+        Err(e) => {
+            warn!("翻译失败 ({}), 请求 {} 次: {}", provider, attempts, e);
+            TranslateResult {
+                provider: Some(provider),
+                attempts,
+                last_error,
+                ..TranslateResult::failed(e.to_string())
+            }
+        }
+    }
+}
+
+/// 选中的是单个单词时查询词典，失败只记录日志，不影响翻译结果
+async fn lookup_alternatives(
+    settings: &AppSettings,
+    provider: &str,
+    req: &TranslateRequest,
+    detected_lang: Option<&str>,
+) -> Vec<DictionaryEntry> {
+    if !is_single_word(&req.text) {
+        return Vec::new();
+    }
+    let provider = match build_provider(provider, settings) {
+        Ok(p) if p.capabilities().dictionary => p,
+        _ => return Vec::new(),
+    };
+
+    // 词典查询必须指定源语言，自动检测时使用翻译返回的检测结果
+    let mut req = req.clone();
+    if providers::is_auto_detect(&req.source_lang) {
+        match detected_lang {
+            Some(lang) => req.source_lang = lang.to_string(),
+            None => return Vec::new(),
+        }
+    }
+
+    match provider.lookup(&req).await {
+        Ok(entries) => {
+            debug!("词典查询到 {} 个释义", entries.len());
+            entries
+        }
+        Err(e) => {
+            warn!("词典查询失败: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// 中止正在进行的翻译请求
+fn cancel_in_flight() {
+    if let Some((request_id, handle)) = IN_FLIGHT.lock().unwrap().take() {
+        info!("取消翻译请求 [{}]", request_id);
+        handle.abort();
+    }
+}
+
+/// 在可中止的任务中运行 `task`，先取消上一个请求，并登记为正在进行的请求
+async fn spawn_cancellable<T, F>(request_id: &str, task: F) -> Result<T, tokio::task::JoinError>
+where
+    T: Send + 'static,
+    F: Future<Output = T> + Send + 'static,
+{
+    cancel_in_flight();
+    let handle = tokio::spawn(task);
+    *IN_FLIGHT.lock().unwrap() = Some((request_id.to_string(), handle.abort_handle()));
+
+    let result = handle.await;
     {
-        Ok(resp) => {
-            let status = resp.status();
-            info!("API 响应状态码: {}", status);
-
-            let body = resp.text().await.unwrap_or_default();
-            debug!("API 响应内容: {}", body);
-
-            match serde_json::from_str::<TranslateResponse>(&body) {
-                Ok(data) => {
-                    info!("API 返回 code: {}", data.code);
-                    if data.code == 200 {
-                        let result_text = data.data.unwrap_or_default();
-                        info!("翻译成功, 结果长度: {} 字符", result_text.len());
-                        debug!("翻译结果: {}", result_text);
-                        TranslateResult {
-                            success: true,
-                            text: result_text,
-                            error: None,
-                        }
-                    } else {
-                        warn!("API 返回非 200 code: {}", data.code);
-                        TranslateResult {
-                            success: false,
-                            text: String::new(),
-                            error: Some(format!("翻译服务返回错误码: {}", data.code)),
-                        }
-                    }
-                }
+        // 只清除自己的记录，此时可能已经登记了更新的请求
+        let mut in_flight = IN_FLIGHT.lock().unwrap();
+        if matches!(&*in_flight, Some((id, _)) if id == request_id) {
+            *in_flight = None;
+        }
+    }
+    result
+}
+
+/// 在可中止的任务中翻译，先取消上一个请求；被新请求取消时返回 `cancelled` 状态
+async fn run_cancellable<F>(request_id: String, task: F) -> TranslateResult
+where
+    F: Future<Output = TranslateResult> + Send + 'static,
+{
+    match spawn_cancellable(&request_id, task).await {
+        Ok(result) => result,
+        Err(e) if e.is_cancelled() => {
+            info!("翻译请求 [{}] 已取消", request_id);
+            TranslateResult::cancelled()
+        }
+        Err(e) => TranslateResult::failed(format!("翻译任务异常: {}", e)),
+    }
+}
+
+/// `request_id` 由 `translate-text` 事件带给前端，未传入时生成一个新的；
+/// `bypass_cache` 为 true 时忽略缓存重新翻译
+#[tauri::command]
+async fn translate(text: String, request_id: Option<String>, bypass_cache: Option<bool>) -> TranslateResult {
+    let request_id = request_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    info!("开始翻译 [{}], 文本长度: {} 字符", request_id, text.len());
+    debug!("翻译文本: {}", text);
+
+    let source_app = source_app_for(&request_id);
+    run_cancellable(request_id, run_translation(text, None, bypass_cache.unwrap_or(false), source_app)).await
+}
+
+/// 流式翻译：增量文本通过 `translate-chunk` 事件推送，结束后发送 `translate-done`
+///
+/// 前端用 `request_id` 区分不同请求，忽略过期请求的事件
+#[tauri::command]
+async fn translate_stream(
+    app: AppHandle,
+    text: String,
+    request_id: String,
+    bypass_cache: Option<bool>,
+) -> TranslateResult {
+    info!("开始流式翻译 [{}], 文本长度: {} 字符", request_id, text.len());
+    debug!("翻译文本: {}", text);
+
+    let chunk_app = app.clone();
+    let chunk_request_id = request_id.clone();
+    let source_app = source_app_for(&request_id);
+    let result = run_cancellable(request_id.clone(), async move {
+        let on_chunk = |delta: &str| {
+            let _ = chunk_app.emit("translate-chunk", TranslateChunkPayload {
+                request_id: chunk_request_id.clone(),
+                delta: delta.to_string(),
+            });
+        };
+        run_translation(text, Some(&on_chunk), bypass_cache.unwrap_or(false), source_app).await
+    }).await;
+
+    let _ = app.emit("translate-done", TranslateDonePayload {
+        request_id,
+        result: result.clone(),
+    });
+    result
+}
+
+#[derive(Clone, Serialize)]
+struct CompareResultPayload {
+    request_id: String,
+    provider: String,
+    latency_ms: u64,
+    success: bool,
+    text: String,
+    error: Option<String>,
+}
+
+/// 对比模式：同时请求多个服务，每个服务按设置重试，返回后立即发送 `translate-compare-result` 事件，
+/// 全部完成后发送 `translate-compare-done`；与单个翻译一样会被新的翻译请求取消
+#[tauri::command]
+async fn translate_compare(
+    app: AppHandle,
+    text: String,
+    request_id: String,
+    provider_ids: Option<Vec<String>>,
+) -> Vec<CompareResultPayload> {
+    let settings = SETTINGS_CACHE.read().await.clone();
+    let ids = match provider_ids {
+        Some(ids) if !ids.is_empty() => ids,
+        _ if !settings.compare_providers.is_empty() => settings.compare_providers.clone(),
+        _ => providers::fallback::provider_chain(&settings),
+    };
+    info!("开始对比翻译 [{}], 服务: {:?}", request_id, ids);
+
+    let req = TranslateRequest {
+        text,
+        source_lang: settings.source_lang.clone(),
+        target_lang: settings.target_lang.clone(),
+    };
+
+    let task_app = app.clone();
+    let task_request_id = request_id.clone();
+    let compared = spawn_cancellable(&request_id, async move {
+        let mut tasks = tokio::task::JoinSet::new();
+        for id in ids {
+            let settings = settings.clone();
+            let req = req.clone();
+            tasks.spawn(async move {
+                let started = Instant::now();
+                let result = providers::translate_with(&id, &settings, &req).await;
+                (id, started.elapsed(), result)
+            });
+        }
+
+        let mut results = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            let Ok((provider, elapsed, result)) = joined else {
+                continue;
+            };
+            let payload = match result {
+                Ok(translation) => CompareResultPayload {
+                    request_id: task_request_id.clone(),
+                    provider,
+                    latency_ms: elapsed.as_millis() as u64,
+                    success: true,
+                    text: translation.text,
+                    error: None,
+                },
                 Err(e) => {
-                    error!("解析 API 响应失败: {}", e);
-                    error!("原始响应: {}", body);
-                    TranslateResult {
+                    warn!("对比翻译失败 ({}): {}", provider, e);
+                    CompareResultPayload {
+                        request_id: task_request_id.clone(),
+                        provider,
+                        latency_ms: elapsed.as_millis() as u64,
                         success: false,
                         text: String::new(),
-                        error: Some("翻译服务响应格式错误".to_string()),
+                        error: Some(e.to_string()),
                     }
                 }
-            }
+            };
+            let _ = task_app.emit("translate-compare-result", payload.clone());
+            results.push(payload);
+        }
+        results
+    })
+    .await;
+
+    // 被取消时丢弃 JoinSet，尚未返回的服务请求随之中止
+    let results = match compared {
+        Ok(results) => results,
+        Err(e) if e.is_cancelled() => {
+            info!("对比翻译 [{}] 已取消", request_id);
+            Vec::new()
         }
         Err(e) => {
-            error!("API 请求失败: {}", e);
-            TranslateResult {
-                success: false,
-                text: String::new(),
-                error: Some(format!("网络连接失败: {}", e)),
-            }
+            warn!("对比翻译任务异常: {}", e);
+            Vec::new()
         }
-    }
+    };
+
+    let _ = app.emit("translate-compare-done", request_id);
+    results
 }
 
 #[tauri::command]
@@ -334,7 +773,11 @@ fn get_mouse_position() -> (i32, i32, f64, f64) {
 
 #[derive(Clone, Serialize)]
 struct TranslateEventPayload {
+    /// 前端调用 `translate` 时原样传回，用于取消与区分请求
+    request_id: String,
     text: String,
+    /// 选中的是单词或短语，弹窗显示加入生词本的按钮
+    starrable: bool,
     x: i32,
     y: i32,
 }
@@ -369,9 +812,18 @@ fn trigger_translate(app: &AppHandle) {
     // 等待快捷键释放，避免快捷键的修饰键（如 Ctrl）与模拟的 Ctrl+C 冲突
     thread::sleep(Duration::from_millis(100));
 
+    // 新的翻译开始，之前还没返回的请求结果已经没有意义
+    cancel_in_flight();
+
+    // 模拟复制之前前台仍是选中文本所在的应用
+    let source_app = foreground_app();
+
     if let Some(text) = get_selected_text() {
-        info!("发送翻译事件到前端");
-        let payload = TranslateEventPayload { text, x, y };
+        let request_id = uuid::Uuid::new_v4().to_string();
+        info!("发送翻译事件到前端 [{}]", request_id);
+        *SOURCE_APP.lock().unwrap() = source_app.map(|app| (request_id.clone(), app));
+        let starrable = vocabulary::is_candidate(&text);
+        let payload = TranslateEventPayload { request_id, text, starrable, x, y };
         let _ = app.emit("translate-text", payload);
         // 不在后端强制显示窗口，交由前端控制
     } else {
@@ -386,6 +838,10 @@ async fn get_settings(app: AppHandle) -> Result<AppSettings, String> {
     let store = app.store("settings.json")
         .map_err(|e| format!("Failed to access store: {}", e))?;
 
+    let provider = store.get("provider")
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_else(|| DEFAULT_PROVIDER.to_string());
+
     let api_key = store.get("api_key")
         .and_then(|v| v.as_str().map(String::from))
         .unwrap_or_default();
@@ -419,7 +875,100 @@ async fn get_settings(app: AppHandle) -> Result<AppSettings, String> {
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
 
+    let deeplx = store.get("deeplx")
+        .and_then(|v| serde_json::from_value::<DeepLxConfig>(v).ok())
+        .unwrap_or_default();
+
+    let deepl = store.get("deepl")
+        .and_then(|v| serde_json::from_value::<DeepLConfig>(v).ok())
+        .unwrap_or_default();
+
+    let openai = store.get("openai")
+        .and_then(|v| serde_json::from_value::<OpenAiConfig>(v).ok())
+        .unwrap_or_default();
+
+    let libretranslate = store.get("libretranslate")
+        .and_then(|v| serde_json::from_value::<LibreTranslateConfig>(v).ok())
+        .unwrap_or_default();
+
+    let ollama = store.get("ollama")
+        .and_then(|v| serde_json::from_value::<OllamaConfig>(v).ok())
+        .unwrap_or_default();
+
+    let offline = store.get("offline")
+        .and_then(|v| serde_json::from_value::<OfflineConfig>(v).ok())
+        .unwrap_or_default();
+
+    let google = store.get("google")
+        .and_then(|v| serde_json::from_value::<GoogleConfig>(v).ok())
+        .unwrap_or_default();
+
+    let azure = store.get("azure")
+        .and_then(|v| serde_json::from_value::<AzureConfig>(v).ok())
+        .unwrap_or_default();
+
+    let baidu = store.get("baidu")
+        .and_then(|v| serde_json::from_value::<BaiduConfig>(v).ok())
+        .unwrap_or_default();
+
+    let youdao = store.get("youdao")
+        .and_then(|v| serde_json::from_value::<YoudaoConfig>(v).ok())
+        .unwrap_or_default();
+
+    let tencent = store.get("tencent")
+        .and_then(|v| serde_json::from_value::<TencentConfig>(v).ok())
+        .unwrap_or_default();
+
+    let aws = store.get("aws")
+        .and_then(|v| serde_json::from_value::<AwsConfig>(v).ok())
+        .unwrap_or_default();
+
+    let custom = store.get("custom")
+        .and_then(|v| serde_json::from_value::<CustomConfig>(v).ok())
+        .unwrap_or_default();
+
+    let command = store.get("command")
+        .and_then(|v| serde_json::from_value::<CommandConfig>(v).ok())
+        .unwrap_or_default();
+
+    let network = store.get("network")
+        .and_then(|v| serde_json::from_value::<NetworkConfig>(v).ok())
+        .unwrap_or_default();
+    // 证书等文件失效时仍然加载其余设置，翻译时会提示具体错误
+    if let Err(e) = providers::http::configure(&network) {
+        warn!("网络设置无效: {}", e);
+    }
+
+    let retry = store.get("retry")
+        .and_then(|v| serde_json::from_value::<RetryConfig>(v).ok())
+        .unwrap_or_default();
+
+    let throttle = store.get("throttle")
+        .and_then(|v| serde_json::from_value::<ThrottleConfig>(v).ok())
+        .unwrap_or_default();
+
+    let cache = store.get("cache")
+        .and_then(|v| serde_json::from_value::<CacheConfig>(v).ok())
+        .unwrap_or_default();
+
+    let history = store.get("history")
+        .and_then(|v| serde_json::from_value::<HistoryConfig>(v).ok())
+        .unwrap_or_default();
+
+    let anki = store.get("anki")
+        .and_then(|v| serde_json::from_value::<AnkiConfig>(v).ok())
+        .unwrap_or_default();
+
+    let fallback_providers = store.get("fallback_providers")
+        .and_then(|v| serde_json::from_value::<Vec<String>>(v).ok())
+        .unwrap_or_default();
+
+    let compare_providers = store.get("compare_providers")
+        .and_then(|v| serde_json::from_value::<Vec<String>>(v).ok())
+        .unwrap_or_default();
+
     let settings = AppSettings {
+        provider,
         api_key,
         auto_close_enabled,
         auto_close_timeout,
@@ -428,6 +977,28 @@ async fn get_settings(app: AppHandle) -> Result<AppSettings, String> {
         first_run,
         shortcut,
         auto_start,
+        deeplx,
+        deepl,
+        openai,
+        libretranslate,
+        ollama,
+        offline,
+        google,
+        azure,
+        baidu,
+        youdao,
+        tencent,
+        aws,
+        custom,
+        command,
+        network,
+        retry,
+        throttle,
+        cache,
+        history,
+        anki,
+        fallback_providers,
+        compare_providers,
     };
 
     // Update cache
@@ -446,12 +1017,65 @@ async fn save_settings(
     target_lang: String,
     shortcut: String,
     auto_start: bool,
+    provider: Option<String>,
+    deeplx: Option<DeepLxConfig>,
+    deepl: Option<DeepLConfig>,
+    openai: Option<OpenAiConfig>,
+    libretranslate: Option<LibreTranslateConfig>,
+    ollama: Option<OllamaConfig>,
+    offline: Option<OfflineConfig>,
+    google: Option<GoogleConfig>,
+    azure: Option<AzureConfig>,
+    baidu: Option<BaiduConfig>,
+    youdao: Option<YoudaoConfig>,
+    tencent: Option<TencentConfig>,
+    aws: Option<AwsConfig>,
+    custom: Option<CustomConfig>,
+    command: Option<CommandConfig>,
+    network: Option<NetworkConfig>,
+    retry: Option<RetryConfig>,
+    throttle: Option<ThrottleConfig>,
+    cache: Option<CacheConfig>,
+    history: Option<HistoryConfig>,
+    anki: Option<AnkiConfig>,
+    fallback_providers: Option<Vec<String>>,
+    compare_providers: Option<Vec<String>>,
 ) -> Result<(), String> {
     let auto_close_timeout = normalize_auto_close_timeout(auto_close_timeout);
+    // 旧版设置页不传 provider 相关字段，保留当前配置
+    let current = SETTINGS_CACHE.read().await.clone();
+    let provider = provider.unwrap_or(current.provider);
+    let deeplx = deeplx.unwrap_or(current.deeplx);
+    let deepl = deepl.unwrap_or(current.deepl);
+    let openai = openai.unwrap_or(current.openai);
+    let libretranslate = libretranslate.unwrap_or(current.libretranslate);
+    let ollama = ollama.unwrap_or(current.ollama);
+    let offline = offline.unwrap_or(current.offline);
+    let google = google.unwrap_or(current.google);
+    let azure = azure.unwrap_or(current.azure);
+    let baidu = baidu.unwrap_or(current.baidu);
+    let youdao = youdao.unwrap_or(current.youdao);
+    let tencent = tencent.unwrap_or(current.tencent);
+    let aws = aws.unwrap_or(current.aws);
+    let custom = custom.unwrap_or(current.custom);
+    let command = command.unwrap_or(current.command);
+    let network = network.unwrap_or(current.network);
+    let retry = retry.unwrap_or(current.retry);
+    let throttle = throttle.unwrap_or(current.throttle);
+    let cache = cache.unwrap_or(current.cache);
+    let history = history.unwrap_or(current.history);
+    let anki = anki.unwrap_or(current.anki);
+    let fallback_providers = fallback_providers.unwrap_or(current.fallback_providers);
+    let compare_providers = compare_providers.unwrap_or(current.compare_providers);
+
+    // 先应用网络设置，代理或证书有误时不保存
+    providers::http::configure(&network)
+        .map_err(|e| format!("Invalid network settings: {}", e))?;
 
     let store = app.store("settings.json")
         .map_err(|e| format!("Failed to access store: {}", e))?;
 
+    store.set("provider", serde_json::json!(provider));
     store.set("api_key", serde_json::json!(api_key));
     store.set("auto_close_enabled", serde_json::json!(auto_close_enabled));
     store.set("auto_close_timeout", serde_json::json!(auto_close_timeout));
@@ -459,6 +1083,28 @@ async fn save_settings(
     store.set("target_lang", serde_json::json!(target_lang));
     store.set("shortcut", serde_json::json!(shortcut));
     store.set("auto_start", serde_json::json!(auto_start));
+    store.set("deeplx", serde_json::json!(deeplx));
+    store.set("deepl", serde_json::json!(deepl));
+    store.set("openai", serde_json::json!(openai));
+    store.set("libretranslate", serde_json::json!(libretranslate));
+    store.set("ollama", serde_json::json!(ollama));
+    store.set("offline", serde_json::json!(offline));
+    store.set("google", serde_json::json!(google));
+    store.set("azure", serde_json::json!(azure));
+    store.set("baidu", serde_json::json!(baidu));
+    store.set("youdao", serde_json::json!(youdao));
+    store.set("tencent", serde_json::json!(tencent));
+    store.set("aws", serde_json::json!(aws));
+    store.set("custom", serde_json::json!(custom));
+    store.set("command", serde_json::json!(command));
+    store.set("network", serde_json::json!(network));
+    store.set("retry", serde_json::json!(retry));
+    store.set("throttle", serde_json::json!(throttle));
+    store.set("cache", serde_json::json!(cache));
+    store.set("history", serde_json::json!(history));
+    store.set("anki", serde_json::json!(anki));
+    store.set("fallback_providers", serde_json::json!(fallback_providers));
+    store.set("compare_providers", serde_json::json!(compare_providers));
     store.set("first_run", serde_json::json!(false));
 
     store.save()
@@ -466,6 +1112,7 @@ async fn save_settings(
 
     // Update cache
     let settings = AppSettings {
+        provider,
         api_key,
         auto_close_enabled,
         auto_close_timeout,
@@ -474,6 +1121,28 @@ async fn save_settings(
         first_run: false,
         shortcut: shortcut.clone(),
         auto_start,
+        deeplx,
+        deepl,
+        openai,
+        libretranslate,
+        ollama,
+        offline,
+        google,
+        azure,
+        baidu,
+        youdao,
+        tencent,
+        aws,
+        custom,
+        command,
+        network,
+        retry,
+        throttle,
+        cache,
+        history,
+        anki,
+        fallback_providers,
+        compare_providers,
     };
     *SETTINGS_CACHE.write().await = settings.clone();
 
@@ -491,45 +1160,287 @@ async fn save_settings(
     Ok(())
 }
 
+/// 清空翻译缓存，返回删除的条目数
+#[tauri::command]
+fn clear_cache() -> Result<usize, String> {
+    cache::clear()
+}
+
+/// 分页列出翻译历史，`query` 不为空时在原文与译文中搜索（支持中日韩文字）
+#[tauri::command]
+fn list_history(
+    query: Option<String>,
+    pinned_only: Option<bool>,
+    offset: Option<u64>,
+    limit: Option<u64>,
+) -> Result<HistoryPage, String> {
+    history::list(
+        query.as_deref(),
+        pinned_only.unwrap_or(false),
+        offset.unwrap_or(0),
+        limit.unwrap_or(50),
+    )
+}
+
+/// 删除历史条目，返回实际删除的条目数
+#[tauri::command]
+fn delete_history(ids: Vec<i64>) -> Result<usize, String> {
+    history::delete(&ids)
+}
+
+#[tauri::command]
+fn pin_history(id: i64, pinned: bool) -> Result<(), String> {
+    history::set_pinned(id, pinned)
+}
+
+/// 把满足搜索条件的历史导出为 CSV、JSON Lines、Markdown 表格或 TMX 1.4，返回导出的条目数；
+/// 未指定 `format` 时按扩展名推断
+#[tauri::command]
+fn export_history(
+    path: String,
+    format: Option<HistoryFormat>,
+    query: Option<String>,
+    pinned_only: Option<bool>,
+) -> Result<usize, String> {
+    history_io::export(
+        std::path::Path::new(&path),
+        format,
+        query.as_deref(),
+        pinned_only.unwrap_or(false),
+    )
+}
+
+/// 从上述格式的文件导入历史，跳过与已有条目重复的内容
+#[tauri::command]
+fn import_history(path: String, format: Option<HistoryFormat>) -> Result<ImportSummary, String> {
+    history_io::import(std::path::Path::new(&path), format)
+}
+
+/// 把弹窗中的单词或短语加入生词本，已存在时更新译文
+#[tauri::command]
+fn add_vocabulary(
+    word: String,
+    translation: String,
+    source_lang: String,
+    target_lang: String,
+    phonetic: Option<String>,
+    example: Option<String>,
+) -> Result<VocabularyCard, String> {
+    if !vocabulary::is_candidate(&word) {
+        return Err("Only single words or short phrases can be added to the vocabulary notebook".to_string());
+    }
+    let card = vocabulary::add(&vocabulary::NewCard {
+        word: &word,
+        translation: &translation,
+        source_lang: &source_lang,
+        target_lang: &target_lang,
+        phonetic: phonetic.as_deref(),
+        example: example.as_deref(),
+    })?;
+    refresh_review_menu();
+    Ok(card)
+}
+
+#[tauri::command]
+fn remove_vocabulary(id: i64) -> Result<(), String> {
+    vocabulary::remove(id)?;
+    refresh_review_menu();
+    Ok(())
+}
+
+/// 今日待复习的生词，最早到期的在前
+#[tauri::command]
+fn next_review_cards(limit: Option<u64>) -> Result<Vec<VocabularyCard>, String> {
+    vocabulary::due_cards(limit.unwrap_or(20))
+}
+
+/// 记录复习结果（SM-2 的 0–5 分，3 分及以上为答对），返回安排好下次复习时间的卡片
 #[tauri::command]
-async fn validate_api_key(api_key: String) -> Result<bool, String> {
-    if api_key.trim().is_empty() {
-        return Err("API Key cannot be empty".to_string());
+fn grade_card(id: i64, grade: u8) -> Result<VocabularyCard, String> {
+    let card = vocabulary::grade(id, grade)?;
+    refresh_review_menu();
+    Ok(card)
+}
+
+/// 把选中的历史条目与生词导出为 Anki 牌组（.apkg），返回导出的笔记数；
+/// 两者都不传时导出整个生词本
+#[tauri::command]
+async fn export_anki(
+    path: String,
+    history_ids: Option<Vec<i64>>,
+    card_ids: Option<Vec<i64>>,
+) -> Result<usize, String> {
+    let settings = SETTINGS_CACHE.read().await.clone();
+    let mut notes: Vec<AnkiNote> = Vec::new();
+    if let Some(ids) = &history_ids {
+        notes.extend(history::entries(ids)?.iter().map(AnkiNote::from));
     }
+    if card_ids.is_some() || history_ids.is_none() {
+        notes.extend(vocabulary::cards(card_ids.as_deref())?.iter().map(AnkiNote::from));
+    }
+    anki::export(std::path::Path::new(&path), &settings.anki, &notes)
+}
 
-    // Test the API key with a simple translation
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(10))
-        .build()
-        .map_err(|e| format!("HTTP 客户端初始化失败: {}", e))?;
-    let req = TranslateRequest {
-        text: "test".to_string(),
-        source_lang: "EN".to_string(),
-        target_lang: "ZH".to_string(),
+/// 更新托盘菜单中的今日待复习数
+fn refresh_review_menu() {
+    let Some(item) = REVIEW_MENU_ITEM.get() else {
+        return;
+    };
+    let text = match vocabulary::due_count() {
+        Ok(0) => "今日没有待复习的生词".to_string(),
+        Ok(count) => format!("今日待复习生词: {}", count),
+        Err(_) => "生词本不可用".to_string(),
     };
+    if let Err(e) = item.set_text(text) {
+        warn!("更新托盘菜单失败: {}", e);
+    }
+}
 
-    let url = format!("https://api.deeplx.org/{}/translate", api_key);
+#[tauri::command]
+async fn validate_api_key(
+    api_key: String,
+    provider: Option<String>,
+    deeplx: Option<DeepLxConfig>,
+    deepl: Option<DeepLConfig>,
+    openai: Option<OpenAiConfig>,
+    libretranslate: Option<LibreTranslateConfig>,
+    ollama: Option<OllamaConfig>,
+    offline: Option<OfflineConfig>,
+    google: Option<GoogleConfig>,
+    azure: Option<AzureConfig>,
+    baidu: Option<BaiduConfig>,
+    youdao: Option<YoudaoConfig>,
+    tencent: Option<TencentConfig>,
+    aws: Option<AwsConfig>,
+    custom: Option<CustomConfig>,
+    command: Option<CommandConfig>,
+) -> Result<bool, String> {
+    // Test the API key against the provider being configured (defaults to the saved one).
+    // Empty keys are checked by the provider itself, since self-hosted DeepLX may need none.
+    let mut settings = SETTINGS_CACHE.read().await.clone();
+    settings.api_key = api_key;
+    if let Some(provider) = provider {
+        settings.provider = provider;
+    }
+    if let Some(deeplx) = deeplx {
+        settings.deeplx = deeplx;
+    }
+    if let Some(deepl) = deepl {
+        settings.deepl = deepl;
+    }
+    if let Some(openai) = openai {
+        settings.openai = openai;
+    }
+    if let Some(libretranslate) = libretranslate {
+        settings.libretranslate = libretranslate;
+    }
+    if let Some(ollama) = ollama {
+        settings.ollama = ollama;
+    }
+    if let Some(offline) = offline {
+        settings.offline = offline;
+    }
+    if let Some(google) = google {
+        settings.google = google;
+    }
+    if let Some(azure) = azure {
+        settings.azure = azure;
+    }
+    if let Some(baidu) = baidu {
+        settings.baidu = baidu;
+    }
+    if let Some(youdao) = youdao {
+        settings.youdao = youdao;
+    }
+    if let Some(tencent) = tencent {
+        settings.tencent = tencent;
+    }
+    if let Some(aws) = aws {
+        settings.aws = aws;
+    }
+    if let Some(custom) = custom {
+        settings.custom = custom;
+    }
+    if let Some(command) = command {
+        settings.command = command;
+    }
 
-    match client
-        .post(&url)
-        .json(&req)
-        .send()
+    let provider = build_provider(&settings.provider, &settings).map_err(|e| e.to_string())?;
+    provider
+        .validate()
         .await
-    {
-        Ok(resp) => {
-            let status = resp.status();
-            if status.is_success() {
-                let body = resp.text().await.unwrap_or_default();
-                match serde_json::from_str::<TranslateResponse>(&body) {
-                    Ok(data) if data.code == 200 => Ok(true),
-                    _ => Err("API Key is invalid or API returned an error".to_string()),
-                }
-            } else {
-                Err(format!("API returned status code: {}", status))
-            }
-        }
-        Err(e) => Err(format!("Failed to validate API key: {}", e)),
+        .map(|_| true)
+        .map_err(|e| format!("Failed to validate API key: {}", e))
+}
+
+#[derive(Serialize)]
+struct ProviderInfo {
+    id: String,
+    capabilities: Capabilities,
+    languages: Vec<Language>,
+}
+
+#[tauri::command]
+async fn get_provider_info() -> Result<ProviderInfo, String> {
+    let settings = SETTINGS_CACHE.read().await.clone();
+    let provider = build_provider(&settings.provider, &settings).map_err(|e| e.to_string())?;
+    let languages = provider.supported_languages().await.map_err(|e| e.to_string())?;
+
+    Ok(ProviderInfo {
+        id: provider.id().to_string(),
+        capabilities: provider.capabilities(),
+        languages,
+    })
+}
+
+#[tauri::command]
+async fn get_usage() -> Result<Usage, String> {
+    let settings = SETTINGS_CACHE.read().await.clone();
+    let provider = build_provider(&settings.provider, &settings).map_err(|e| e.to_string())?;
+    provider.usage().await.map_err(|e| e.to_string())
+}
+
+/// 获取 Ollama 已安装的模型，`base_url` 为空时使用已保存的地址
+#[tauri::command]
+async fn list_ollama_models(base_url: Option<String>) -> Result<Vec<OllamaModel>, String> {
+    let mut config = SETTINGS_CACHE.read().await.ollama.clone();
+    if let Some(base_url) = base_url.filter(|u| !u.trim().is_empty()) {
+        config.base_url = base_url;
     }
+
+    let ollama = Ollama::new(config).map_err(|e| e.to_string())?;
+    ollama.list_models().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn list_offline_models() -> Result<Vec<InstalledModel>, String> {
+    let config = SETTINGS_CACHE.read().await.offline.clone();
+    let dir = providers::offline::models_dir(&config).map_err(|e| e.to_string())?;
+    providers::offline::list_models(&dir).map_err(|e| e.to_string())
+}
+
+/// 从本地目录导入 Opus-MT 模型，作为 `source_lang` -> `target_lang` 的离线翻译模型
+#[tauri::command]
+async fn import_offline_model(
+    path: String,
+    source_lang: String,
+    target_lang: String,
+) -> Result<InstalledModel, String> {
+    let config = SETTINGS_CACHE.read().await.offline.clone();
+    let dir = providers::offline::models_dir(&config).map_err(|e| e.to_string())?;
+
+    // 模型文件有数百 MB，复制放到阻塞线程中执行
+    tauri::async_runtime::spawn_blocking(move || {
+        providers::offline::import_model(
+            &dir,
+            std::path::Path::new(&path),
+            &source_lang,
+            &target_lang,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -658,12 +1569,33 @@ pub fn run() {
         .plugin(tauri_plugin_store::Builder::default().build())
         .plugin(tauri_plugin_autostart::init(tauri_plugin_autostart::MacosLauncher::LaunchAgent, None))
         .setup(|app| {
+            match app.path().app_data_dir() {
+                Ok(dir) => {
+                    let _ = providers::offline::DEFAULT_MODELS_DIR.set(dir.join("models"));
+                    if let Err(e) = std::fs::create_dir_all(&dir) {
+                        warn!("创建应用数据目录失败: {}", e);
+                    }
+                    cache::init(&dir.join("translation_cache.sqlite"));
+                    history::init(&dir.join("history.sqlite"));
+                    vocabulary::init(&dir.join("vocabulary.sqlite"));
+                }
+                Err(e) => warn!("无法获取应用数据目录: {}", e),
+            }
+
             // Load settings into cache on startup
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
                 match get_settings(app_handle.clone()).await {
                     Ok(settings) => {
-                        info!("Settings loaded: {:?}", settings);
+                        // 设置中含有各服务的密钥，只记录不敏感的字段
+                        info!(
+                            "Settings loaded: provider={}, {} -> {}, fallback={:?}, shortcut={}",
+                            settings.provider,
+                            settings.source_lang,
+                            settings.target_lang,
+                            settings.fallback_providers,
+                            settings.shortcut
+                        );
 
                         // Register custom shortcut
                         let shortcut_failed = if let Err(e) = update_shortcut(app_handle.clone(), settings.shortcut.clone()).await {
@@ -695,9 +1627,20 @@ pub fn run() {
             });
 
             // Create menu items
+            // 只用于显示今日待复习的生词数，不响应点击
+            let review_item = MenuItem::with_id(app, "review", "今日没有待复习的生词", false, None::<&str>)?;
+            let separator = PredefinedMenuItem::separator(app)?;
             let settings_item = MenuItem::with_id(app, "settings", "设置", true, None::<&str>)?;
             let quit = MenuItem::with_id(app, "quit", "退出程序", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&settings_item, &quit])?;
+            let menu = Menu::with_items(app, &[&review_item, &separator, &settings_item, &quit])?;
+            let _ = REVIEW_MENU_ITEM.set(review_item);
+            refresh_review_menu();
+            tauri::async_runtime::spawn(async {
+                loop {
+                    tokio::time::sleep(REVIEW_MENU_REFRESH).await;
+                    refresh_review_menu();
+                }
+            });
 
             // 使用应用图标创建系统托盘
             let tray_icon = if let Some(icon) = app.default_window_icon() {
@@ -736,10 +1679,28 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             translate,
+            translate_stream,
+            translate_compare,
             get_mouse_position,
             get_settings,
             save_settings,
             validate_api_key,
+            clear_cache,
+            list_history,
+            delete_history,
+            pin_history,
+            export_history,
+            import_history,
+            add_vocabulary,
+            remove_vocabulary,
+            next_review_cards,
+            grade_card,
+            export_anki,
+            get_provider_info,
+            get_usage,
+            list_ollama_models,
+            list_offline_models,
+            import_offline_model,
             validate_shortcut,
             update_shortcut,
             open_settings_window,
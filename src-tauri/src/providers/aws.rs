@@ -0,0 +1,367 @@
+use async_trait::async_trait;
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+
+use super::retry::retry_after;
+use super::signing::{hmac_sha256, sha256_hex, SigningTime};
+use super::{
+    http_client, is_auto_detect, Capabilities, Language, ProviderError, TranslateRequest,
+    Translation, TranslationProvider,
+};
+
+pub const ID: &str = "aws";
+
+const SERVICE: &str = "translate";
+const TARGET_PREFIX: &str = "AWSShineFrontendService_20170701";
+const CONTENT_TYPE: &str = "application/x-amz-json-1.1";
+
+/// IAM 用户的访问密钥，需要 `translate:TranslateText` 与 `translate:ListLanguages` 权限
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct AwsConfig {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// 使用临时凭据时填写
+    pub session_token: String,
+    pub region: String,
+}
+
+impl Default for AwsConfig {
+    fn default() -> Self {
+        Self {
+            access_key_id: String::new(),
+            secret_access_key: String::new(),
+            session_token: String::new(),
+            region: "us-east-1".to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct TranslateTextRequest<'a> {
+    text: &'a str,
+    source_language_code: &'a str,
+    target_language_code: &'a str,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TranslateTextResponse {
+    translated_text: String,
+    source_language_code: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct ListLanguagesRequest {
+    display_language_code: &'static str,
+    max_results: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ListLanguagesResponse {
+    languages: Vec<AwsLanguage>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AwsLanguage {
+    language_name: String,
+    language_code: String,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    #[serde(rename = "__type")]
+    kind: String,
+    #[serde(alias = "Message")]
+    message: Option<String>,
+}
+
+/// 待签名请求中参与签名的部分
+pub(crate) struct SignedRequest<'a> {
+    pub host: &'a str,
+    pub target: &'a str,
+    pub payload: &'a str,
+    pub session_token: Option<&'a str>,
+}
+
+/// SigV4 签名，返回 `Authorization` 头
+pub(crate) fn authorization(
+    access_key_id: &str,
+    secret_access_key: &str,
+    region: &str,
+    time: &SigningTime,
+    req: &SignedRequest,
+) -> String {
+    let amz_date = time.amz_date();
+    // 规范头按名称排序，名称小写
+    let mut headers = vec![
+        ("content-type", CONTENT_TYPE),
+        ("host", req.host),
+        ("x-amz-date", amz_date.as_str()),
+        ("x-amz-target", req.target),
+    ];
+    if let Some(token) = req.session_token {
+        headers.push(("x-amz-security-token", token));
+    }
+    headers.sort_by_key(|(name, _)| *name);
+
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{}:{}\n", name, value.trim()))
+        .collect();
+    let signed_headers = headers
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(";");
+    let canonical_request = format!(
+        "POST\n/\n\n{}\n{}\n{}",
+        canonical_headers,
+        signed_headers,
+        sha256_hex(req.payload.as_bytes())
+    );
+
+    let date = time.date_compact();
+    let scope = format!("{}/{}/{}/aws4_request", date, region, SERVICE);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        sha256_hex(canonical_request.as_bytes())
+    );
+
+    let k_date = hmac_sha256(format!("AWS4{}", secret_access_key).as_bytes(), &date);
+    let k_region = hmac_sha256(&k_date, region);
+    let k_service = hmac_sha256(&k_region, SERVICE);
+    let k_signing = hmac_sha256(&k_service, "aws4_request");
+    let signature = hex::encode(hmac_sha256(&k_signing, &string_to_sign));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        access_key_id, scope, signed_headers, signature
+    )
+}
+
+/// `__type` 形如 `com.amazonaws...#ThrottlingException`，只看 `#` 之后的部分
+fn api_error(status: u16, error: ErrorResponse) -> ProviderError {
+    let kind = error.kind.rsplit('#').next().unwrap_or_default();
+    let message = error.message.unwrap_or_default();
+    match kind {
+        "UnrecognizedClientException"
+        | "InvalidSignatureException"
+        | "AccessDeniedException"
+        | "IncompleteSignature"
+        | "ExpiredTokenException" => {
+            ProviderError::Config(format!("AWS 凭据无效或没有权限: {}", message))
+        }
+        "ThrottlingException" | "LimitExceededException" | "TooManyRequestsException" => {
+            ProviderError::Quota("AWS Translate 请求过于频繁，请稍后再试".to_string())
+        }
+        "ServiceUnavailableException" | "InternalServerException" => ProviderError::Status(503),
+        _ if status >= 500 => ProviderError::Status(status),
+        _ => ProviderError::Config(format!("AWS Translate: {}", message)),
+    }
+}
+
+/// AWS 的语言代码为小写两位代码，简体中文为 `zh`
+fn to_aws_lang(lang: &str) -> String {
+    lang.to_lowercase()
+}
+
+fn from_aws_lang(lang: &str) -> String {
+    if lang.len() == 2 {
+        lang.to_uppercase()
+    } else {
+        lang.to_string()
+    }
+}
+
+pub struct Aws {
+    config: AwsConfig,
+    client: reqwest::Client,
+}
+
+impl Aws {
+    pub fn new(config: AwsConfig) -> Result<Self, ProviderError> {
+        if config.access_key_id.trim().is_empty() || config.secret_access_key.trim().is_empty() {
+            return Err(ProviderError::Config(
+                "AWS Access Key 未配置，请在系统托盘菜单中打开设置".to_string(),
+            ));
+        }
+        if config.region.trim().is_empty() {
+            return Err(ProviderError::Config("AWS 区域未配置".to_string()));
+        }
+        Ok(Self {
+            config,
+            client: http_client(ID)?,
+        })
+    }
+
+    async fn call<Req: Serialize, Resp: for<'de> Deserialize<'de>>(
+        &self,
+        action: &str,
+        body: &Req,
+    ) -> Result<Resp, ProviderError> {
+        let payload =
+            serde_json::to_string(body).map_err(|e| ProviderError::Parse(e.to_string()))?;
+        let region = self.config.region.trim();
+        let host = format!("translate.{}.amazonaws.com", region);
+        let target = format!("{}.{}", TARGET_PREFIX, action);
+        let session_token = Some(self.config.session_token.trim()).filter(|t| !t.is_empty());
+        let time = SigningTime::now();
+        let authorization = authorization(
+            self.config.access_key_id.trim(),
+            self.config.secret_access_key.trim(),
+            region,
+            &time,
+            &SignedRequest {
+                host: &host,
+                target: &target,
+                payload: &payload,
+                session_token,
+            },
+        );
+
+        let url = format!("https://{}/", host);
+        info!("请求 API: {} ({})", url, action);
+        let mut builder = self
+            .client
+            .post(&url)
+            .header("Authorization", authorization)
+            .header("Content-Type", CONTENT_TYPE)
+            .header("X-Amz-Date", time.amz_date())
+            .header("X-Amz-Target", target);
+        if let Some(token) = session_token {
+            builder = builder.header("X-Amz-Security-Token", token);
+        }
+
+        let resp = builder.body(payload).send().await.map_err(|e| {
+            error!("API 请求失败: {}", e);
+            ProviderError::from(e)
+        })?;
+
+        let status = resp.status();
+        info!("API 响应状态码: {}", status);
+
+        let retry_after = retry_after(resp.headers());
+        let body = resp.text().await.unwrap_or_default();
+        debug!("API 响应内容: {}", body);
+
+        if !status.is_success() {
+            warn!(
+                "AWS Translate 返回非 2xx 状态码: {}, 内容: {}",
+                status, body
+            );
+            return Err(match serde_json::from_str::<ErrorResponse>(&body) {
+                Ok(e) => api_error(status.as_u16(), e),
+                Err(_) => ProviderError::Status(status.as_u16()),
+            }
+            .with_retry_after(retry_after));
+        }
+
+        serde_json::from_str(&body).map_err(|e| {
+            error!("解析 API 响应失败: {}", e);
+            error!("原始响应: {}", body);
+            ProviderError::Parse(e.to_string())
+        })
+    }
+}
+
+#[async_trait]
+impl TranslationProvider for Aws {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            auto_detect: true,
+            ..Capabilities::default()
+        }
+    }
+
+    async fn supported_languages(&self) -> Result<Vec<Language>, ProviderError> {
+        let resp: ListLanguagesResponse = self
+            .call(
+                "ListLanguages",
+                &ListLanguagesRequest {
+                    display_language_code: "zh",
+                    max_results: 500,
+                },
+            )
+            .await?;
+        Ok(resp
+            .languages
+            .into_iter()
+            .filter(|l| l.language_code != "auto")
+            .map(|l| Language {
+                code: from_aws_lang(&l.language_code),
+                name: l.language_name,
+            })
+            .collect())
+    }
+
+    async fn translate(&self, req: &TranslateRequest) -> Result<Translation, ProviderError> {
+        let source = if is_auto_detect(&req.source_lang) {
+            "auto".to_string()
+        } else {
+            to_aws_lang(&req.source_lang)
+        };
+        let target = to_aws_lang(&req.target_lang);
+
+        let resp: TranslateTextResponse = self
+            .call(
+                "TranslateText",
+                &TranslateTextRequest {
+                    text: &req.text,
+                    source_language_code: &source,
+                    target_language_code: &target,
+                },
+            )
+            .await?;
+
+        Ok(Translation {
+            text: resp.translated_text,
+            detected_source_lang: resp
+                .source_language_code
+                .filter(|_| is_auto_detect(&req.source_lang))
+                .map(|l| from_aws_lang(&l)),
+        })
+    }
+
+    /// ListLanguages 不计费，用来检查凭据与区域
+    async fn validate(&self) -> Result<(), ProviderError> {
+        self.supported_languages().await.map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn authorization_matches_known_vector() {
+        let header = authorization(
+            "AKIDEXAMPLE",
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "us-east-1",
+            &SigningTime::from_timestamp(1709251199),
+            &SignedRequest {
+                host: "translate.us-east-1.amazonaws.com",
+                target: "AWSShineFrontendService_20170701.TranslateText",
+                payload: r#"{"Text":"hi","SourceLanguageCode":"auto","TargetLanguageCode":"zh"}"#,
+                session_token: Some("TOK"),
+            },
+        );
+        assert_eq!(
+            header,
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20240229/us-east-1/translate/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date;x-amz-security-token;x-amz-target, \
+             Signature=af07f1bc789ce860dae389592f26301300f84702f41e59e922f8b6447abd6ff1"
+        );
+    }
+}
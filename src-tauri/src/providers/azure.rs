@@ -0,0 +1,350 @@
+use async_trait::async_trait;
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::retry::retry_after;
+use super::{
+    http_client, is_auto_detect, Capabilities, DictionaryEntry, Language, ProviderError,
+    TranslateRequest, Translation, TranslationProvider,
+};
+
+pub const ID: &str = "azure";
+
+const DEFAULT_ENDPOINT: &str = "https://api.cognitive.microsofttranslator.com";
+const API_VERSION: &str = "3.0";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct AzureConfig {
+    /// 该服务专用的订阅 Key，为空时使用通用的 `api_key`
+    pub api_key: String,
+    /// 资源所在区域，如 `eastasia`；全局资源留空
+    pub region: String,
+    pub endpoint: String,
+}
+
+impl Default for AzureConfig {
+    fn default() -> Self {
+        Self {
+            api_key: String::new(),
+            region: String::new(),
+            endpoint: DEFAULT_ENDPOINT.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct TextItem<'a> {
+    #[serde(rename = "Text")]
+    text: &'a str,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TranslateItem {
+    detected_language: Option<DetectedLanguage>,
+    translations: Vec<AzureTranslation>,
+}
+
+#[derive(Deserialize)]
+struct DetectedLanguage {
+    language: String,
+}
+
+#[derive(Deserialize)]
+struct AzureTranslation {
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct LookupItem {
+    translations: Vec<LookupTranslation>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LookupTranslation {
+    display_target: String,
+    pos_tag: String,
+    #[serde(default)]
+    back_translations: Vec<BackTranslation>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackTranslation {
+    display_text: String,
+}
+
+#[derive(Deserialize)]
+struct LanguagesResponse {
+    translation: HashMap<String, LanguageName>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LanguageName {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: ErrorDetail,
+}
+
+/// `code` 为 HTTP 状态码后接三位细分编号，如 403001 表示免费额度用完
+#[derive(Deserialize)]
+struct ErrorDetail {
+    #[serde(default)]
+    code: i64,
+    message: String,
+}
+
+pub struct Azure {
+    api_key: String,
+    config: AzureConfig,
+    client: reqwest::Client,
+}
+
+impl Azure {
+    pub fn new(api_key: String, config: AzureConfig) -> Result<Self, ProviderError> {
+        if api_key.trim().is_empty() {
+            return Err(ProviderError::Config(
+                "API Key未配置，请在系统托盘菜单中打开设置".to_string(),
+            ));
+        }
+        Ok(Self {
+            api_key,
+            config,
+            client: http_client(ID)?,
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        let endpoint = if self.config.endpoint.trim().is_empty() {
+            DEFAULT_ENDPOINT
+        } else {
+            self.config.endpoint.trim().trim_end_matches('/')
+        };
+        format!("{}{}", endpoint, path)
+    }
+
+    /// 发送带订阅 Key 的 POST 请求，返回响应内容
+    async fn post(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+        text: &str,
+    ) -> Result<String, ProviderError> {
+        let url = self.url(path);
+        info!("请求 API: {}", url);
+
+        let mut builder = self
+            .client
+            .post(&url)
+            .query(&[("api-version", API_VERSION)])
+            .query(query)
+            .header("Ocp-Apim-Subscription-Key", self.api_key.trim())
+            .json(&[TextItem { text }]);
+        if !self.config.region.trim().is_empty() {
+            builder = builder.header("Ocp-Apim-Subscription-Region", self.config.region.trim());
+        }
+
+        let resp = builder.send().await.map_err(|e| {
+            error!("API 请求失败: {}", e);
+            ProviderError::from(e)
+        })?;
+
+        let status = resp.status();
+        info!("API 响应状态码: {}", status);
+
+        let retry_after = retry_after(resp.headers());
+        let body = resp.text().await.unwrap_or_default();
+        debug!("API 响应内容: {}", body);
+
+        if !status.is_success() {
+            warn!("Azure 返回非 2xx 状态码: {}, 内容: {}", status, body);
+            return Err(api_error(status.as_u16(), &body).with_retry_after(retry_after));
+        }
+
+        Ok(body)
+    }
+}
+
+/// 错误码见 https://learn.microsoft.com/azure/ai-services/translator/reference/v3-0-reference#errors
+///
+/// 403 也可能是区域或终结点不匹配、资源被禁用，只有 403001 才是免费额度用完
+fn api_error(status: u16, body: &str) -> ProviderError {
+    let detail = serde_json::from_str::<ErrorResponse>(body)
+        .map(|e| e.error)
+        .ok();
+    match (status, detail) {
+        (401, _) => ProviderError::Config("Azure 订阅 Key 或区域无效".to_string()),
+        (403, Some(detail)) if detail.code == 403001 => {
+            ProviderError::Quota("Azure 本月免费额度已用完".to_string())
+        }
+        (403, detail) => ProviderError::Config(format!(
+            "Azure 拒绝了请求，请检查区域、终结点与资源状态: {}",
+            detail.map(|d| d.message).unwrap_or_default()
+        )),
+        (429, _) => ProviderError::Quota("Azure 请求过于频繁，请稍后再试".to_string()),
+        (400, Some(detail)) => ProviderError::Config(format!("Azure: {}", detail.message)),
+        (code, _) => ProviderError::Status(code),
+    }
+}
+
+/// Azure 的中文代码区分简繁，其余语言为小写两位代码
+fn to_azure_lang(lang: &str) -> String {
+    match lang {
+        "ZH" => "zh-Hans".to_string(),
+        other if other.len() == 2 => other.to_lowercase(),
+        other => other.to_string(),
+    }
+}
+
+fn from_azure_lang(lang: &str) -> String {
+    match lang {
+        "zh-Hans" => "ZH".to_string(),
+        other if other.len() == 2 => other.to_uppercase(),
+        other => other.to_string(),
+    }
+}
+
+fn parse<T: for<'de> Deserialize<'de>>(body: &str) -> Result<T, ProviderError> {
+    serde_json::from_str(body).map_err(|e| {
+        error!("解析 API 响应失败: {}", e);
+        error!("原始响应: {}", body);
+        ProviderError::Parse(e.to_string())
+    })
+}
+
+#[async_trait]
+impl TranslationProvider for Azure {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            auto_detect: true,
+            dictionary: true,
+            ..Capabilities::default()
+        }
+    }
+
+    /// 语言列表接口不需要鉴权，名称按简体中文返回
+    async fn supported_languages(&self) -> Result<Vec<Language>, ProviderError> {
+        let resp = self
+            .client
+            .get(self.url("/languages"))
+            .query(&[("api-version", API_VERSION), ("scope", "translation")])
+            .header("Accept-Language", "zh-Hans")
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(ProviderError::Status(status.as_u16()));
+        }
+
+        let body = resp.text().await.unwrap_or_default();
+        let mut languages: Vec<Language> = parse::<LanguagesResponse>(&body)?
+            .translation
+            .into_iter()
+            .map(|(code, l)| Language {
+                code: from_azure_lang(&code),
+                name: l.name,
+            })
+            .collect();
+        languages.sort_by(|a, b| a.code.cmp(&b.code));
+        Ok(languages)
+    }
+
+    async fn translate(&self, req: &TranslateRequest) -> Result<Translation, ProviderError> {
+        let target = to_azure_lang(&req.target_lang);
+        let source = to_azure_lang(&req.source_lang);
+        let mut query = vec![("to", target.as_str())];
+        if !is_auto_detect(&req.source_lang) {
+            query.push(("from", source.as_str()));
+        }
+
+        let body = self.post("/translate", &query, &req.text).await?;
+        let item = parse::<Vec<TranslateItem>>(&body)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| ProviderError::Parse("响应为空".to_string()))?;
+        let text = item
+            .translations
+            .into_iter()
+            .next()
+            .map(|t| t.text)
+            .ok_or_else(|| ProviderError::Parse("响应中没有 translations".to_string()))?;
+
+        Ok(Translation {
+            text,
+            detected_source_lang: item.detected_language.map(|d| from_azure_lang(&d.language)),
+        })
+    }
+
+    /// 词典查询必须指定源语言，自动检测时由调用方传入检测结果
+    async fn lookup(&self, req: &TranslateRequest) -> Result<Vec<DictionaryEntry>, ProviderError> {
+        if is_auto_detect(&req.source_lang) {
+            return Err(ProviderError::Config("词典查询需要指定源语言".to_string()));
+        }
+        let source = to_azure_lang(&req.source_lang);
+        let target = to_azure_lang(&req.target_lang);
+
+        let body = self
+            .post(
+                "/dictionary/lookup",
+                &[("from", source.as_str()), ("to", target.as_str())],
+                req.text.trim(),
+            )
+            .await?;
+        let entries = parse::<Vec<LookupItem>>(&body)?
+            .into_iter()
+            .flat_map(|item| item.translations)
+            .map(|t| DictionaryEntry {
+                translation: t.display_target,
+                pos: t.pos_tag,
+                back_translations: t
+                    .back_translations
+                    .into_iter()
+                    .map(|b| b.display_text)
+                    .collect(),
+            })
+            .collect();
+        Ok(entries)
+    }
+
+    async fn validate(&self) -> Result<(), ProviderError> {
+        let body = self.post("/translate", &[("to", "en")], "ok").await?;
+        parse::<Vec<TranslateItem>>(&body).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_403001_is_reported_as_exhausted_quota() {
+        let quota = api_error(
+            403,
+            r#"{"error":{"code":403001,"message":"The operation is not allowed because the subscription has exceeded its free quota."}}"#,
+        );
+        assert!(matches!(quota, ProviderError::Quota(_)));
+
+        let denied = api_error(
+            403,
+            r#"{"error":{"code":403000,"message":"The operation is not allowed."}}"#,
+        );
+        assert!(
+            matches!(&denied, ProviderError::Config(m) if m.ends_with("The operation is not allowed."))
+        );
+        assert!(matches!(api_error(403, ""), ProviderError::Config(_)));
+        assert!(matches!(api_error(429, ""), ProviderError::Quota(_)));
+        assert!(matches!(api_error(503, ""), ProviderError::Status(503)));
+    }
+}
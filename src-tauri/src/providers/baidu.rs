@@ -0,0 +1,239 @@
+use async_trait::async_trait;
+use log::{debug, error, info, warn};
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::retry::retry_after;
+use super::{
+    default_languages, http_client, is_auto_detect, Capabilities, Language, ProviderError,
+    TranslateRequest, Translation, TranslationProvider,
+};
+
+pub const ID: &str = "baidu";
+
+const API_URL: &str = "https://fanyi-api.baidu.com/api/trans/vip/translate";
+
+/// 百度翻译开放平台的通用文本翻译凭据
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct BaiduConfig {
+    pub app_id: String,
+    pub secret: String,
+}
+
+#[derive(Deserialize)]
+struct BaiduResponse {
+    from: Option<String>,
+    trans_result: Option<Vec<TransResult>>,
+    /// 出错时返回，部分接口为字符串，部分为数字
+    error_code: Option<serde_json::Value>,
+    error_msg: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TransResult {
+    dst: String,
+}
+
+/// 签名：`MD5(appid + q + salt + 密钥)` 的小写十六进制
+pub(crate) fn sign(app_id: &str, query: &str, salt: &str, secret: &str) -> String {
+    let mut hasher = Md5::new();
+    hasher.update(app_id.as_bytes());
+    hasher.update(query.as_bytes());
+    hasher.update(salt.as_bytes());
+    hasher.update(secret.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// 设置中的语言代码转换为百度的代码，百度沿用了部分三字母代码
+fn to_baidu_lang(lang: &str) -> &str {
+    match lang {
+        "ZH" => "zh",
+        "EN" => "en",
+        "JA" => "jp",
+        "KO" => "kor",
+        "FR" => "fra",
+        "DE" => "de",
+        "ES" => "spa",
+        "RU" => "ru",
+        "IT" => "it",
+        "PT" => "pt",
+        "AR" => "ara",
+        "NL" => "nl",
+        "PL" => "pl",
+        "TR" => "tr",
+        other => other,
+    }
+}
+
+fn from_baidu_lang(lang: &str) -> String {
+    match lang {
+        "jp" => "JA".to_string(),
+        "kor" => "KO".to_string(),
+        "fra" => "FR".to_string(),
+        "spa" => "ES".to_string(),
+        "ara" => "AR".to_string(),
+        other => other.to_uppercase(),
+    }
+}
+
+fn error_code(value: &serde_json::Value) -> Option<i64> {
+    match value {
+        serde_json::Value::String(s) => s.parse().ok(),
+        other => other.as_i64(),
+    }
+}
+
+/// 百度的错误码见 https://fanyi-api.baidu.com/doc/21
+fn api_error(code: i64, message: &str) -> ProviderError {
+    match code {
+        52001 | 52002 => ProviderError::Network(format!("百度翻译: {}", message)),
+        52003 | 54001 | 58000 | 90107 => {
+            ProviderError::Config("百度翻译 APP ID 或密钥无效".to_string())
+        }
+        54003 | 54005 => ProviderError::Quota("百度翻译请求过于频繁，请稍后再试".to_string()),
+        54004 => ProviderError::Quota("百度翻译账户余额不足".to_string()),
+        58001 => ProviderError::Config("百度翻译不支持该语言方向".to_string()),
+        code => ProviderError::Api(code),
+    }
+}
+
+pub struct Baidu {
+    config: BaiduConfig,
+    client: reqwest::Client,
+}
+
+impl Baidu {
+    pub fn new(config: BaiduConfig) -> Result<Self, ProviderError> {
+        if config.app_id.trim().is_empty() || config.secret.trim().is_empty() {
+            return Err(ProviderError::Config(
+                "百度翻译 APP ID 或密钥未配置，请在系统托盘菜单中打开设置".to_string(),
+            ));
+        }
+        Ok(Self {
+            config,
+            client: http_client(ID)?,
+        })
+    }
+}
+
+#[async_trait]
+impl TranslationProvider for Baidu {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            auto_detect: true,
+            ..Capabilities::default()
+        }
+    }
+
+    async fn supported_languages(&self) -> Result<Vec<Language>, ProviderError> {
+        Ok(default_languages())
+    }
+
+    async fn translate(&self, req: &TranslateRequest) -> Result<Translation, ProviderError> {
+        let app_id = self.config.app_id.trim();
+        let salt = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis()
+            .to_string();
+        let from = if is_auto_detect(&req.source_lang) {
+            "auto"
+        } else {
+            to_baidu_lang(&req.source_lang)
+        };
+        let sign = sign(app_id, &req.text, &salt, self.config.secret.trim());
+
+        info!("请求 API: {}", API_URL);
+        let resp = self
+            .client
+            .post(API_URL)
+            .form(&[
+                ("q", req.text.as_str()),
+                ("from", from),
+                ("to", to_baidu_lang(&req.target_lang)),
+                ("appid", app_id),
+                ("salt", salt.as_str()),
+                ("sign", sign.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(|e| {
+                error!("API 请求失败: {}", e);
+                ProviderError::from(e)
+            })?;
+
+        let status = resp.status();
+        info!("API 响应状态码: {}", status);
+
+        let retry_after = retry_after(resp.headers());
+        let body = resp.text().await.unwrap_or_default();
+        debug!("API 响应内容: {}", body);
+
+        if !status.is_success() {
+            warn!("百度翻译返回非 2xx 状态码: {}", status);
+            return Err(ProviderError::Status(status.as_u16()).with_retry_after(retry_after));
+        }
+
+        let data = serde_json::from_str::<BaiduResponse>(&body).map_err(|e| {
+            error!("解析 API 响应失败: {}", e);
+            error!("原始响应: {}", body);
+            ProviderError::Parse(e.to_string())
+        })?;
+
+        if let Some(code) = data.error_code.as_ref().and_then(error_code) {
+            // 52000 表示成功，部分情况下也会出现在响应中
+            if code != 52000 {
+                let message = data.error_msg.unwrap_or_default();
+                warn!("百度翻译返回错误码: {}, {}", code, message);
+                return Err(api_error(code, &message));
+            }
+        }
+
+        // 多段文本按换行拆分后逐段返回
+        let text = data
+            .trans_result
+            .ok_or_else(|| ProviderError::Parse("响应中没有 trans_result".to_string()))?
+            .into_iter()
+            .map(|r| r.dst)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Ok(Translation {
+            text,
+            detected_source_lang: data
+                .from
+                .filter(|_| is_auto_detect(&req.source_lang))
+                .map(|l| from_baidu_lang(&l)),
+        })
+    }
+
+    /// 百度没有免费的校验接口，翻译一个单词检查凭据
+    async fn validate(&self) -> Result<(), ProviderError> {
+        self.translate(&TranslateRequest {
+            text: "hello".to_string(),
+            source_lang: "EN".to_string(),
+            target_lang: "ZH".to_string(),
+        })
+        .await
+        .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_matches_official_example() {
+        assert_eq!(
+            sign("2015063000000001", "apple", "1435660288", "12345678"),
+            "f89f9594663708c1605f3d736d01d2d4"
+        );
+    }
+}
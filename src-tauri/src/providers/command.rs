@@ -0,0 +1,221 @@
+use async_trait::async_trait;
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::process::Command;
+
+use super::{
+    default_languages, is_auto_detect, Capabilities, Language, ProviderError, TranslateRequest,
+    Translation, TranslationProvider, AUTO_DETECT,
+};
+
+pub const ID: &str = "command";
+
+/// Windows 下启动控制台程序时不弹出黑窗口
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// 作为翻译服务的本地程序
+///
+/// 每次翻译启动一次进程：向 stdin 写入一个 JSON 请求后关闭，
+/// 从 stdout 读取一个 JSON 结果，stderr 的内容写入日志
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct CommandConfig {
+    pub program: String,
+    pub args: Vec<String>,
+    /// 工作目录，为空时继承当前目录
+    pub working_dir: String,
+    pub timeout_secs: u64,
+    /// 原样传给程序的术语表
+    pub glossary: String,
+}
+
+impl Default for CommandConfig {
+    fn default() -> Self {
+        Self {
+            program: String::new(),
+            args: Vec::new(),
+            working_dir: String::new(),
+            timeout_secs: 30,
+            glossary: String::new(),
+        }
+    }
+}
+
+/// 写入 stdin 的请求
+#[derive(Serialize)]
+struct CommandRequest<'a> {
+    text: &'a str,
+    source: &'a str,
+    target: &'a str,
+    glossary: &'a str,
+}
+
+/// 从 stdout 读取的结果，字段与 `TranslateResult` 相同
+#[derive(Deserialize)]
+struct CommandResponse {
+    success: bool,
+    #[serde(default)]
+    text: String,
+    error: Option<String>,
+    detected_lang: Option<String>,
+}
+
+pub struct ExternalCommand {
+    config: CommandConfig,
+}
+
+impl ExternalCommand {
+    pub fn new(config: CommandConfig) -> Result<Self, ProviderError> {
+        if config.program.trim().is_empty() {
+            return Err(ProviderError::Config(
+                "未配置翻译程序，请在系统托盘菜单中打开设置".to_string(),
+            ));
+        }
+        Ok(Self { config })
+    }
+
+    fn command(&self) -> Command {
+        let mut cmd = Command::new(self.config.program.trim());
+        cmd.args(&self.config.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if !self.config.working_dir.trim().is_empty() {
+            cmd.current_dir(self.config.working_dir.trim());
+        }
+        #[cfg(windows)]
+        cmd.creation_flags(CREATE_NO_WINDOW);
+        cmd
+    }
+
+    /// 运行一次程序，返回 stdout 的全部内容
+    async fn run(&self, input: &[u8]) -> Result<String, ProviderError> {
+        let program = self.config.program.trim();
+        info!("启动翻译程序: {} {:?}", program, self.config.args);
+
+        let mut child = self
+            .command()
+            .spawn()
+            .map_err(|e| ProviderError::Config(format!("无法启动翻译程序 {}: {}", program, e)))?;
+
+        let mut stdin = child.stdin.take().expect("stdin 已设置为 piped");
+        let mut stdout = child.stdout.take().expect("stdout 已设置为 piped");
+        let stderr = child.stderr.take().expect("stderr 已设置为 piped");
+
+        // stderr 逐行转发到日志，同时避免管道写满导致程序阻塞
+        let stderr_task = tokio::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            let mut last = String::new();
+            while let Ok(Some(line)) = lines.next_line().await {
+                warn!("[翻译程序 stderr] {}", line);
+                last = line;
+            }
+            last
+        });
+
+        let io = async {
+            stdin.write_all(input).await?;
+            // 关闭 stdin，程序读到 EOF 后开始处理
+            drop(stdin);
+            let mut output = String::new();
+            stdout.read_to_string(&mut output).await?;
+            let status = child.wait().await?;
+            Ok::<_, std::io::Error>((status, output))
+        };
+
+        let timeout = Duration::from_secs(self.config.timeout_secs.max(1));
+        let (status, output) = match tokio::time::timeout(timeout, io).await {
+            Ok(result) => {
+                result.map_err(|e| ProviderError::Config(format!("与翻译程序通信失败: {}", e)))?
+            }
+            Err(_) => {
+                // 超时后 child 被丢弃，kill_on_drop 会结束进程
+                error!("翻译程序超过 {} 秒未返回", timeout.as_secs());
+                return Err(ProviderError::Network(format!(
+                    "翻译程序超过 {} 秒未返回",
+                    timeout.as_secs()
+                )));
+            }
+        };
+
+        let last_stderr = stderr_task.await.unwrap_or_default();
+        if !status.success() {
+            warn!("翻译程序退出状态: {}", status);
+            return Err(ProviderError::Config(if last_stderr.is_empty() {
+                format!("翻译程序异常退出 ({})", status)
+            } else {
+                format!("翻译程序异常退出 ({}): {}", status, last_stderr)
+            }));
+        }
+
+        Ok(output)
+    }
+}
+
+#[async_trait]
+impl TranslationProvider for ExternalCommand {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            auto_detect: true,
+            ..Capabilities::default()
+        }
+    }
+
+    async fn supported_languages(&self) -> Result<Vec<Language>, ProviderError> {
+        Ok(default_languages())
+    }
+
+    async fn translate(&self, req: &TranslateRequest) -> Result<Translation, ProviderError> {
+        let input = serde_json::to_vec(&CommandRequest {
+            text: &req.text,
+            source: if is_auto_detect(&req.source_lang) {
+                AUTO_DETECT
+            } else {
+                &req.source_lang
+            },
+            target: &req.target_lang,
+            glossary: self.config.glossary.trim(),
+        })
+        .map_err(|e| ProviderError::Parse(e.to_string()))?;
+
+        let output = self.run(&input).await?;
+        debug!("翻译程序输出: {}", output);
+
+        let data = serde_json::from_str::<CommandResponse>(output.trim()).map_err(|e| {
+            error!("解析翻译程序输出失败: {}", e);
+            error!("原始输出: {}", output);
+            ProviderError::Parse(e.to_string())
+        })?;
+
+        if !data.success {
+            return Err(ProviderError::Config(
+                data.error.unwrap_or_else(|| "翻译程序返回失败".to_string()),
+            ));
+        }
+
+        Ok(Translation {
+            text: data.text,
+            detected_source_lang: data.detected_lang,
+        })
+    }
+
+    /// 用一段示例文本运行一次程序，检查能否启动以及输出格式是否正确
+    async fn validate(&self) -> Result<(), ProviderError> {
+        self.translate(&TranslateRequest {
+            text: "hello".to_string(),
+            source_lang: "EN".to_string(),
+            target_lang: "ZH".to_string(),
+        })
+        .await
+        .map(|_| ())
+    }
+}
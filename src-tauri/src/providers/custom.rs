@@ -0,0 +1,327 @@
+use async_trait::async_trait;
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_json_path::JsonPath;
+use std::fmt;
+
+use super::retry::retry_after;
+use super::template::render;
+use super::{
+    default_languages, http_client, is_auto_detect, Capabilities, Language, ProviderError,
+    TranslateRequest, Translation, TranslationProvider, AUTO_DETECT,
+};
+
+pub const ID: &str = "custom";
+
+/// 请求头模板，`value` 中可以使用占位符
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct HeaderTemplate {
+    pub name: String,
+    pub value: String,
+}
+
+/// 通过配置定义的 HTTP 翻译服务
+///
+/// `url`、请求头与 `body` 中可以使用 `{text}`、`{source}`、`{target}`、`{api_key}`
+/// 占位符；响应用 JSONPath 取出译文与错误码。默认值与 DeepLX 的接口一致
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct CustomConfig {
+    /// 该服务专用的 API Key，为空时使用通用的 `api_key`
+    pub api_key: String,
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<HeaderTemplate>,
+    /// JSON 请求体模板，为空时不发送请求体
+    pub body: String,
+    /// 译文所在位置，如 `$.data`
+    pub result_path: String,
+    /// 错误码所在位置，为空时不检查
+    pub error_path: String,
+    /// 表示成功的错误码，为空时错误码位置有任何值都视为失败
+    pub success_value: String,
+    /// 自动检测时识别出的源语言所在位置，可以为空
+    pub detected_lang_path: String,
+}
+
+impl Default for CustomConfig {
+    fn default() -> Self {
+        Self {
+            api_key: String::new(),
+            method: "POST".to_string(),
+            url: "https://api.deeplx.org/{api_key}/translate".to_string(),
+            headers: Vec::new(),
+            body: r#"{"text": "{text}", "source_lang": "{source}", "target_lang": "{target}"}"#
+                .to_string(),
+            result_path: "$.data".to_string(),
+            error_path: "$.code".to_string(),
+            success_value: "200".to_string(),
+            detected_lang_path: String::new(),
+        }
+    }
+}
+
+/// 模板中出错的部分，校验失败时告诉用户该改哪里
+#[derive(Debug, Clone, Copy)]
+enum TemplatePart<'a> {
+    Method,
+    Url,
+    Header(&'a str),
+    Body,
+    ResultPath,
+    ErrorPath,
+    DetectedLangPath,
+}
+
+impl fmt::Display for TemplatePart<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplatePart::Method => write!(f, "请求方法"),
+            TemplatePart::Url => write!(f, "URL 模板"),
+            TemplatePart::Header(name) => write!(f, "请求头 {}", name),
+            TemplatePart::Body => write!(f, "请求体模板"),
+            TemplatePart::ResultPath => write!(f, "译文路径 (result_path)"),
+            TemplatePart::ErrorPath => write!(f, "错误码路径 (error_path)"),
+            TemplatePart::DetectedLangPath => write!(f, "源语言路径 (detected_lang_path)"),
+        }
+    }
+}
+
+fn template_error(part: TemplatePart<'_>, detail: impl fmt::Display) -> ProviderError {
+    ProviderError::Config(format!("自定义服务的{}有误: {}", part, detail))
+}
+
+fn parse_path(part: TemplatePart<'_>, path: &str) -> Result<Option<JsonPath>, ProviderError> {
+    if path.trim().is_empty() {
+        return Ok(None);
+    }
+    JsonPath::parse(path.trim())
+        .map(Some)
+        .map_err(|e| template_error(part, e))
+}
+
+/// 放进 JSON 字符串中的值：只转义，不加引号，模板中写 `"{text}"`
+fn json_escape(value: &str) -> String {
+    let quoted = serde_json::to_string(value).unwrap_or_default();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+/// 取出 JSONPath 指向的第一个值
+fn select<'a>(path: &JsonPath, value: &'a Value) -> Option<&'a Value> {
+    path.query(value).first()
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+pub struct Custom {
+    api_key: String,
+    config: CustomConfig,
+    method: reqwest::Method,
+    result_path: JsonPath,
+    error_path: Option<JsonPath>,
+    detected_lang_path: Option<JsonPath>,
+    client: reqwest::Client,
+}
+
+impl Custom {
+    /// 创建时就解析请求方法与 JSONPath，模板写错时尽早报告
+    pub fn new(api_key: String, config: CustomConfig) -> Result<Self, ProviderError> {
+        let method = reqwest::Method::from_bytes(config.method.trim().to_uppercase().as_bytes())
+            .map_err(|e| template_error(TemplatePart::Method, e))?;
+        if config.url.trim().is_empty() {
+            return Err(template_error(TemplatePart::Url, "不能为空"));
+        }
+        let result_path = parse_path(TemplatePart::ResultPath, &config.result_path)?
+            .ok_or_else(|| template_error(TemplatePart::ResultPath, "不能为空"))?;
+        let error_path = parse_path(TemplatePart::ErrorPath, &config.error_path)?;
+        let detected_lang_path =
+            parse_path(TemplatePart::DetectedLangPath, &config.detected_lang_path)?;
+
+        Ok(Self {
+            api_key,
+            config,
+            method,
+            result_path,
+            error_path,
+            detected_lang_path,
+            client: http_client(ID)?,
+        })
+    }
+
+    fn build_request(
+        &self,
+        req: &TranslateRequest,
+    ) -> Result<reqwest::RequestBuilder, ProviderError> {
+        let source = if is_auto_detect(&req.source_lang) {
+            AUTO_DETECT
+        } else {
+            req.source_lang.as_str()
+        };
+        let values = [
+            ("text", req.text.as_str()),
+            ("source", source),
+            ("target", req.target_lang.as_str()),
+            ("api_key", self.api_key.trim()),
+        ];
+
+        let url = render(self.config.url.trim(), &values, |v| {
+            urlencoding::encode(v).into_owned()
+        });
+        let url = reqwest::Url::parse(&url).map_err(|e| template_error(TemplatePart::Url, e))?;
+        let mut builder = self.client.request(self.method.clone(), url);
+
+        for header in &self.config.headers {
+            if header.name.trim().is_empty() {
+                continue;
+            }
+            let name = reqwest::header::HeaderName::from_bytes(header.name.trim().as_bytes())
+                .map_err(|e| template_error(TemplatePart::Header(&header.name), e))?;
+            let value = render(&header.value, &values, str::to_string);
+            let value = reqwest::header::HeaderValue::from_str(&value)
+                .map_err(|e| template_error(TemplatePart::Header(&header.name), e))?;
+            builder = builder.header(name, value);
+        }
+
+        if !self.config.body.trim().is_empty() {
+            let body = render(&self.config.body, &values, json_escape);
+            // 先解析一遍，模板写错时指出是请求体的问题，而不是等服务端报错
+            let body = serde_json::from_str::<Value>(&body)
+                .map_err(|e| template_error(TemplatePart::Body, e))?;
+            builder = builder.json(&body);
+        }
+
+        Ok(builder)
+    }
+
+    /// 按配置的 JSONPath 解析响应
+    fn parse_response(&self, body: &str) -> Result<Translation, ProviderError> {
+        let data = serde_json::from_str::<Value>(body).map_err(|e| {
+            error!("解析 API 响应失败: {}", e);
+            error!("原始响应: {}", body);
+            ProviderError::Parse(e.to_string())
+        })?;
+
+        if let Some(path) = &self.error_path {
+            let success = self.config.success_value.trim();
+            match select(path, &data) {
+                Some(Value::Null) | None => {}
+                Some(code) if !success.is_empty() && value_to_string(code) == success => {}
+                Some(code) => {
+                    warn!("自定义服务返回错误: {}", code);
+                    return Err(match code.as_i64() {
+                        Some(code) => ProviderError::Api(code),
+                        None => ProviderError::Config(format!(
+                            "自定义服务返回错误: {}",
+                            value_to_string(code)
+                        )),
+                    });
+                }
+            }
+        }
+
+        let text = match select(&self.result_path, &data) {
+            Some(Value::String(text)) => text.clone(),
+            Some(Value::Array(items)) => items
+                .iter()
+                .map(value_to_string)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Some(other) => {
+                return Err(template_error(
+                    TemplatePart::ResultPath,
+                    format!("指向的不是文本: {}", other),
+                ))
+            }
+            None => {
+                return Err(template_error(
+                    TemplatePart::ResultPath,
+                    format!("在响应中没有匹配到内容，响应为: {}", data),
+                ))
+            }
+        };
+
+        let detected_source_lang = self
+            .detected_lang_path
+            .as_ref()
+            .and_then(|path| select(path, &data))
+            .map(value_to_string)
+            .map(|lang| lang.to_uppercase());
+
+        Ok(Translation {
+            text,
+            detected_source_lang,
+        })
+    }
+}
+
+#[async_trait]
+impl TranslationProvider for Custom {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            auto_detect: self.detected_lang_path.is_some(),
+            ..Capabilities::default()
+        }
+    }
+
+    async fn supported_languages(&self) -> Result<Vec<Language>, ProviderError> {
+        Ok(default_languages())
+    }
+
+    async fn translate(&self, req: &TranslateRequest) -> Result<Translation, ProviderError> {
+        let builder = self.build_request(req)?;
+        info!("请求自定义服务: {} {}", self.method, self.config.url);
+
+        let resp = builder.send().await.map_err(|e| {
+            error!("API 请求失败: {}", e);
+            ProviderError::from(e)
+        })?;
+
+        let status = resp.status();
+        info!("API 响应状态码: {}", status);
+        let retry_after = retry_after(resp.headers());
+
+        let body = resp.text().await.unwrap_or_default();
+        debug!("API 响应内容: {}", body);
+
+        if !status.is_success() {
+            warn!("自定义服务返回非 2xx 状态码: {}, 内容: {}", status, body);
+            return Err(ProviderError::Status(status.as_u16()).with_retry_after(retry_after));
+        }
+
+        self.parse_response(&body)
+            .map_err(|e| e.with_retry_after(retry_after))
+    }
+
+    /// 用一段示例文本走一遍完整流程，出错时说明是模板的哪一部分
+    async fn validate(&self) -> Result<(), ProviderError> {
+        self.translate(&TranslateRequest {
+            text: "hello".to_string(),
+            source_lang: "EN".to_string(),
+            target_lang: "ZH".to_string(),
+        })
+        .await
+        .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_keeps_unknown_braces() {
+        let values = [("text", "a\"b")];
+        assert_eq!(render("{x} {text} {", &values, json_escape), "{x} a\\\"b {");
+    }
+}
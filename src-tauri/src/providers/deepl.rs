@@ -0,0 +1,233 @@
+use async_trait::async_trait;
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+
+use super::retry::retry_after;
+use super::{
+    http_client, is_auto_detect, Capabilities, Language, ProviderError, TranslateRequest,
+    Translation, TranslationProvider, Usage,
+};
+
+pub const ID: &str = "deepl";
+
+const FREE_BASE_URL: &str = "https://api-free.deepl.com";
+const PRO_BASE_URL: &str = "https://api.deepl.com";
+
+/// DeepL 账号类型，决定请求的域名
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeepLPlan {
+    /// 根据 API Key 判断：Free 账号的 Key 以 `:fx` 结尾
+    #[default]
+    Auto,
+    Free,
+    Pro,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct DeepLConfig {
+    /// 该服务专用的 API Key，为空时使用通用的 `api_key`
+    pub api_key: String,
+    pub plan: DeepLPlan,
+}
+
+#[derive(Serialize)]
+struct DeepLRequest<'a> {
+    text: [&'a str; 1],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_lang: Option<&'a str>,
+    target_lang: &'a str,
+}
+
+#[derive(Deserialize)]
+struct DeepLResponse {
+    translations: Vec<DeepLTranslation>,
+}
+
+#[derive(Deserialize)]
+struct DeepLTranslation {
+    text: String,
+    detected_source_language: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct UsageResponse {
+    character_count: u64,
+    character_limit: u64,
+}
+
+#[derive(Deserialize)]
+struct LanguageResponse {
+    language: String,
+    name: String,
+}
+
+pub struct DeepL {
+    api_key: String,
+    base_url: &'static str,
+    client: reqwest::Client,
+}
+
+impl DeepL {
+    pub fn new(api_key: String, config: DeepLConfig) -> Result<Self, ProviderError> {
+        if api_key.trim().is_empty() {
+            return Err(ProviderError::Config(
+                "API Key未配置，请在系统托盘菜单中打开设置".to_string(),
+            ));
+        }
+        let base_url = match config.plan {
+            DeepLPlan::Free => FREE_BASE_URL,
+            DeepLPlan::Pro => PRO_BASE_URL,
+            DeepLPlan::Auto if api_key.trim().ends_with(":fx") => FREE_BASE_URL,
+            DeepLPlan::Auto => PRO_BASE_URL,
+        };
+        Ok(Self {
+            api_key,
+            base_url,
+            client: http_client(ID)?,
+        })
+    }
+
+    fn auth_header(&self) -> String {
+        format!("DeepL-Auth-Key {}", self.api_key.trim())
+    }
+
+    /// DeepL 不再接受不带地区的 EN/PT 作为目标语言
+    fn target_lang(lang: &str) -> &str {
+        match lang {
+            "EN" => "EN-US",
+            "PT" => "PT-PT",
+            other => other,
+        }
+    }
+
+    async fn get(&self, path: &str) -> Result<String, ProviderError> {
+        let url = format!("{}{}", self.base_url, path);
+        let resp = self
+            .client
+            .get(&url)
+            .header("Authorization", self.auth_header())
+            .send()
+            .await?;
+
+        let status = resp.status();
+        let retry_after = retry_after(resp.headers());
+        let body = resp.text().await.unwrap_or_default();
+        if !status.is_success() {
+            warn!("DeepL {} 返回状态码: {}, 内容: {}", path, status, body);
+            return Err(Self::status_error(status.as_u16()).with_retry_after(retry_after));
+        }
+        Ok(body)
+    }
+
+    fn status_error(status: u16) -> ProviderError {
+        match status {
+            403 => ProviderError::Config("DeepL API Key 无效".to_string()),
+            429 => ProviderError::Quota("DeepL 请求过于频繁，请稍后再试".to_string()),
+            456 => ProviderError::Quota("DeepL 本月字符额度已用完".to_string()),
+            code => ProviderError::Status(code),
+        }
+    }
+}
+
+#[async_trait]
+impl TranslationProvider for DeepL {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            auto_detect: true,
+            usage: true,
+            ..Capabilities::default()
+        }
+    }
+
+    async fn supported_languages(&self) -> Result<Vec<Language>, ProviderError> {
+        let body = self.get("/v2/languages?type=target").await?;
+        let languages = serde_json::from_str::<Vec<LanguageResponse>>(&body)
+            .map_err(|e| ProviderError::Parse(e.to_string()))?;
+
+        Ok(languages
+            .into_iter()
+            .map(|l| Language {
+                code: l.language,
+                name: l.name,
+            })
+            .collect())
+    }
+
+    async fn translate(&self, req: &TranslateRequest) -> Result<Translation, ProviderError> {
+        let url = format!("{}/v2/translate", self.base_url);
+        info!("请求 API: {}", url);
+
+        let body = DeepLRequest {
+            text: [&req.text],
+            source_lang: if is_auto_detect(&req.source_lang) {
+                None
+            } else {
+                Some(&req.source_lang)
+            },
+            target_lang: Self::target_lang(&req.target_lang),
+        };
+
+        let resp = self
+            .client
+            .post(&url)
+            .header("Authorization", self.auth_header())
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| {
+                error!("API 请求失败: {}", e);
+                ProviderError::from(e)
+            })?;
+
+        let status = resp.status();
+        info!("API 响应状态码: {}", status);
+        let retry_after = retry_after(resp.headers());
+
+        let body = resp.text().await.unwrap_or_default();
+        debug!("API 响应内容: {}", body);
+
+        if !status.is_success() {
+            warn!("DeepL 返回非 2xx 状态码: {}", status);
+            return Err(Self::status_error(status.as_u16()).with_retry_after(retry_after));
+        }
+
+        let data = serde_json::from_str::<DeepLResponse>(&body).map_err(|e| {
+            error!("解析 API 响应失败: {}", e);
+            error!("原始响应: {}", body);
+            ProviderError::Parse(e.to_string())
+        })?;
+
+        let translation = data
+            .translations
+            .into_iter()
+            .next()
+            .ok_or_else(|| ProviderError::Parse("响应中没有 translations".to_string()))?;
+
+        Ok(Translation {
+            text: translation.text,
+            detected_source_lang: translation.detected_source_language,
+        })
+    }
+
+    async fn validate(&self) -> Result<(), ProviderError> {
+        // /v2/usage 不消耗字符额度，适合用来校验 Key
+        self.usage().await.map(|_| ())
+    }
+
+    async fn usage(&self) -> Result<Usage, ProviderError> {
+        let body = self.get("/v2/usage").await?;
+        let data = serde_json::from_str::<UsageResponse>(&body)
+            .map_err(|e| ProviderError::Parse(e.to_string()))?;
+
+        Ok(Usage {
+            character_count: data.character_count,
+            character_limit: data.character_limit,
+        })
+    }
+}
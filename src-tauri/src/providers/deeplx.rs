@@ -0,0 +1,240 @@
+use async_trait::async_trait;
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+
+use super::retry::retry_after;
+use super::{
+    default_languages, http_client, Capabilities, Language, ProviderError, TranslateRequest,
+    Translation, TranslationProvider,
+};
+
+pub const ID: &str = "deeplx";
+
+const DEFAULT_BASE_URL: &str = "https://api.deeplx.org";
+
+/// API Key 的传递方式
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeepLxAuth {
+    /// `{base_url}/{api_key}/translate`，api.deeplx.org 使用的方式
+    #[default]
+    Path,
+    /// `Authorization: Bearer {api_key}`，自建实例设置了 TOKEN 时使用
+    Bearer,
+    /// 不需要鉴权的自建实例
+    None,
+}
+
+/// DeepLX 提供的翻译路由
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeepLxRoute {
+    /// `/translate`，免费接口
+    #[default]
+    Translate,
+    /// `/v1/translate`，基于 DeepL Pro 账号的 dl_session
+    V1,
+    /// `/v2/translate`，兼容 DeepL 官方 API 的格式
+    V2,
+}
+
+impl DeepLxRoute {
+    fn path(self) -> &'static str {
+        match self {
+            DeepLxRoute::Translate => "/translate",
+            DeepLxRoute::V1 => "/v1/translate",
+            DeepLxRoute::V2 => "/v2/translate",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct DeepLxConfig {
+    /// 该服务专用的 API Key，为空时使用通用的 `api_key`
+    pub api_key: String,
+    pub base_url: String,
+    pub auth: DeepLxAuth,
+    pub route: DeepLxRoute,
+}
+
+impl Default for DeepLxConfig {
+    fn default() -> Self {
+        Self {
+            api_key: String::new(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            auth: DeepLxAuth::default(),
+            route: DeepLxRoute::default(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TranslateResponse {
+    code: i64,
+    data: Option<String>,
+}
+
+#[derive(Serialize)]
+struct V2Request<'a> {
+    text: [&'a str; 1],
+    source_lang: &'a str,
+    target_lang: &'a str,
+}
+
+#[derive(Deserialize)]
+struct V2Response {
+    translations: Vec<V2Translation>,
+}
+
+#[derive(Deserialize)]
+struct V2Translation {
+    text: String,
+    detected_source_language: Option<String>,
+}
+
+pub struct DeepLx {
+    api_key: String,
+    config: DeepLxConfig,
+    client: reqwest::Client,
+}
+
+impl DeepLx {
+    pub fn new(api_key: String, config: DeepLxConfig) -> Result<Self, ProviderError> {
+        if config.auth != DeepLxAuth::None && api_key.trim().is_empty() {
+            return Err(ProviderError::Config(
+                "API Key未配置，请在系统托盘菜单中打开设置".to_string(),
+            ));
+        }
+        if !config.base_url.starts_with("http://") && !config.base_url.starts_with("https://") {
+            return Err(ProviderError::Config(format!(
+                "DeepLX 服务地址无效: {}",
+                config.base_url
+            )));
+        }
+        Ok(Self {
+            api_key,
+            config,
+            client: http_client(ID)?,
+        })
+    }
+
+    fn endpoint(&self) -> String {
+        let base = self.config.base_url.trim_end_matches('/');
+        match self.config.auth {
+            DeepLxAuth::Path => format!("{}/{}{}", base, self.api_key, self.config.route.path()),
+            DeepLxAuth::Bearer | DeepLxAuth::None => {
+                format!("{}{}", base, self.config.route.path())
+            }
+        }
+    }
+
+    async fn send(&self, req: &TranslateRequest) -> Result<Translation, ProviderError> {
+        let url = self.endpoint();
+        // 路径中含有 API Key，日志里只记录服务地址
+        info!(
+            "请求 API: {}{}",
+            self.config.base_url,
+            self.config.route.path()
+        );
+
+        let mut builder = self.client.post(&url);
+        if self.config.auth == DeepLxAuth::Bearer {
+            builder = builder.bearer_auth(&self.api_key);
+        }
+        builder = match self.config.route {
+            DeepLxRoute::Translate | DeepLxRoute::V1 => builder.json(req),
+            DeepLxRoute::V2 => builder.json(&V2Request {
+                text: [&req.text],
+                source_lang: &req.source_lang,
+                target_lang: &req.target_lang,
+            }),
+        };
+
+        let resp = builder.send().await.map_err(|e| {
+            error!("API 请求失败: {}", e);
+            ProviderError::from(e)
+        })?;
+
+        let status = resp.status();
+        info!("API 响应状态码: {}", status);
+        let retry_after = retry_after(resp.headers());
+
+        let body = resp.text().await.unwrap_or_default();
+        debug!("API 响应内容: {}", body);
+
+        // 公共实例限流或出错时常返回 HTML 或纯文本，先按状态码处理，
+        // 这样 429 / 5xx 仍然可以重试，不会被当作解析错误
+        if !status.is_success() {
+            warn!("API 返回非 2xx 状态码: {}", status);
+            return Err(ProviderError::Status(status.as_u16()).with_retry_after(retry_after));
+        }
+
+        match self.config.route {
+            DeepLxRoute::Translate | DeepLxRoute::V1 => {
+                let data = serde_json::from_str::<TranslateResponse>(&body).map_err(|e| {
+                    error!("解析 API 响应失败: {}", e);
+                    error!("原始响应: {}", body);
+                    ProviderError::Parse(e.to_string())
+                })?;
+
+                info!("API 返回 code: {}", data.code);
+                if data.code != 200 {
+                    warn!("API 返回非 200 code: {}", data.code);
+                    return Err(ProviderError::Api(data.code).with_retry_after(retry_after));
+                }
+
+                Ok(Translation {
+                    text: data.data.unwrap_or_default(),
+                    detected_source_lang: None,
+                })
+            }
+            DeepLxRoute::V2 => {
+                let data = serde_json::from_str::<V2Response>(&body).map_err(|e| {
+                    error!("解析 API 响应失败: {}", e);
+                    error!("原始响应: {}", body);
+                    ProviderError::Parse(e.to_string())
+                })?;
+
+                let translation =
+                    data.translations.into_iter().next().ok_or_else(|| {
+                        ProviderError::Parse("响应中没有 translations".to_string())
+                    })?;
+
+                Ok(Translation {
+                    text: translation.text,
+                    detected_source_lang: translation.detected_source_language,
+                })
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl TranslationProvider for DeepLx {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+
+    async fn supported_languages(&self) -> Result<Vec<Language>, ProviderError> {
+        Ok(default_languages())
+    }
+
+    async fn translate(&self, req: &TranslateRequest) -> Result<Translation, ProviderError> {
+        self.send(req).await
+    }
+
+    async fn validate(&self) -> Result<(), ProviderError> {
+        let req = TranslateRequest {
+            text: "test".to_string(),
+            source_lang: "EN".to_string(),
+            target_lang: "ZH".to_string(),
+        };
+
+        self.send(&req).await.map(|_| ())
+    }
+}
@@ -0,0 +1,144 @@
+use log::{info, warn};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::guard;
+use super::retry::RetryOutcome;
+use super::{build_provider, ChunkSink, ProviderError, TranslateRequest, Translation};
+use crate::AppSettings;
+
+/// 查找某个服务的缓存译文，命中时不再请求该服务
+pub type CacheLookup<'a> = &'a (dyn Fn(&str) -> Option<Translation> + Send + Sync);
+
+/// 翻译链的结果
+pub struct ChainOutcome {
+    /// 最终应答的服务
+    pub provider: String,
+    /// 译文取自该服务的缓存，没有发出请求
+    pub cached: bool,
+    pub outcome: RetryOutcome<Translation>,
+}
+
+impl ChainOutcome {
+    fn new(
+        provider: &str,
+        cached: bool,
+        result: Result<Translation, ProviderError>,
+        attempts: u32,
+        last_error: Option<ProviderError>,
+    ) -> Self {
+        Self {
+            provider: provider.to_string(),
+            cached,
+            outcome: RetryOutcome {
+                result,
+                attempts,
+                last_error,
+            },
+        }
+    }
+}
+
+/// 按顺序尝试的服务：当前选中的服务在前，之后是备用服务（去重）
+pub fn provider_chain(settings: &AppSettings) -> Vec<String> {
+    let mut chain = vec![settings.provider.clone()];
+    for id in &settings.fallback_providers {
+        if !chain.contains(id) {
+            chain.push(id.clone());
+        }
+    }
+    chain
+}
+
+/// 依次尝试翻译链中的服务，返回最终应答的服务与结果
+///
+/// 轮到某个服务时先用 `cached` 查找它的缓存，命中则直接返回，不会越过前面的服务
+/// 使用备用服务的缓存。每个服务先按 `AppSettings.retry` 重试，仍失败时才切换到下一个服务。
+/// 只有网络错误、429/5xx、额度用尽时才重试或切换；流式翻译一旦输出了内容就不再重试或切换，
+/// 避免弹窗中混入重复或两个服务的译文
+pub async fn translate(
+    settings: &AppSettings,
+    req: &TranslateRequest,
+    on_chunk: Option<ChunkSink<'_>>,
+    cached: Option<CacheLookup<'_>>,
+) -> ChainOutcome {
+    let chain = provider_chain(settings);
+
+    // 全部服务都在熔断中时仍按顺序尝试，由熔断器返回剩余的冷却时间
+    let mut candidates: Vec<&String> = chain.iter().filter(|id| !guard::is_open(id)).collect();
+    if candidates.is_empty() {
+        candidates = chain.iter().collect();
+    }
+
+    let emitted = AtomicBool::new(false);
+    let tracked_chunk = |delta: &str| {
+        emitted.store(true, Ordering::Relaxed);
+        if let Some(on_chunk) = on_chunk {
+            on_chunk(delta);
+        }
+    };
+
+    let mut attempts = 0;
+    let mut last_error = None;
+    let mut last = None;
+    for (index, id) in candidates.iter().enumerate() {
+        let provider = match build_provider(id, settings) {
+            Ok(p) => p,
+            Err(e) => {
+                warn!("跳过未配置的服务 {}: {}", id, e);
+                last = Some((id.to_string(), Err(e)));
+                continue;
+            }
+        };
+
+        if let Some(translation) = cached.and_then(|lookup| lookup(id)) {
+            info!(
+                "命中翻译缓存 ({}), 结果长度: {} 字符",
+                id,
+                translation.text.len()
+            );
+            if let Some(on_chunk) = on_chunk {
+                on_chunk(&translation.text);
+            }
+            return ChainOutcome::new(id, true, Ok(translation), attempts, last_error);
+        }
+
+        let (provider, emitted, tracked_chunk) = (&provider, &emitted, &tracked_chunk);
+        let outcome = guard::run(id, &settings.throttle, &settings.retry, || async move {
+            let result = match on_chunk {
+                Some(_) => provider.translate_stream(req, tracked_chunk).await,
+                None => provider.translate(req).await,
+            };
+            (result, !emitted.load(Ordering::Relaxed))
+        })
+        .await;
+        attempts += outcome.attempts;
+        if outcome.last_error.is_some() {
+            last_error = outcome.last_error;
+        }
+
+        match outcome.result {
+            Ok(translation) => {
+                if index > 0 {
+                    info!("已切换到备用服务 {}", id);
+                }
+                return ChainOutcome::new(id, false, Ok(translation), attempts, last_error);
+            }
+            // 换一个服务也无济于事，或者已经输出了部分译文
+            Err(e) if !e.is_retryable() || emitted.load(Ordering::Relaxed) => {
+                return ChainOutcome::new(id, false, Err(e), attempts, last_error);
+            }
+            Err(e) => {
+                warn!("{} 翻译失败，尝试下一个服务: {}", id, e);
+                last = Some((id.to_string(), Err(e)));
+            }
+        }
+    }
+
+    let (provider, result) = last.unwrap_or_else(|| {
+        (
+            settings.provider.clone(),
+            Err(ProviderError::Config("未配置翻译服务".to_string())),
+        )
+    });
+    ChainOutcome::new(&provider, false, result, attempts, last_error)
+}
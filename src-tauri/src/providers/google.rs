@@ -0,0 +1,472 @@
+use async_trait::async_trait;
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use log::{debug, error, info, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+
+use super::retry::retry_after;
+use super::{
+    http_client, is_auto_detect, Capabilities, Language, ProviderError, TranslateRequest,
+    Translation, TranslationProvider,
+};
+
+pub const ID: &str = "google";
+
+const API_BASE_URL: &str = "https://translation.googleapis.com";
+const TRANSLATION_SCOPE: &str = "https://www.googleapis.com/auth/cloud-translation";
+/// 访问令牌提前刷新的时间，避免请求途中过期
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+/// Cloud Translation 的 API 版本
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GoogleApiVersion {
+    /// Basic 版，使用 API Key 鉴权
+    #[default]
+    V2,
+    /// Advanced 版，使用服务账号签发的 JWT 换取访问令牌
+    V3,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct GoogleConfig {
+    pub version: GoogleApiVersion,
+    /// v2 使用的 API Key，为空时使用通用的 `api_key`
+    pub api_key: String,
+    /// v3 使用的服务账号 JSON 文件路径
+    pub service_account_path: String,
+    /// v3 的项目 ID，为空时使用服务账号中的 project_id
+    pub project_id: String,
+    pub location: String,
+}
+
+impl Default for GoogleConfig {
+    fn default() -> Self {
+        Self {
+            version: GoogleApiVersion::default(),
+            api_key: String::new(),
+            service_account_path: String::new(),
+            project_id: String::new(),
+            location: "global".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ServiceAccount {
+    client_email: String,
+    private_key: String,
+    token_uri: String,
+    project_id: Option<String>,
+}
+
+#[derive(Serialize)]
+struct JwtClaims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: u64,
+    exp: u64,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+#[derive(Serialize)]
+struct V2Request<'a> {
+    q: &'a str,
+    target: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<&'a str>,
+    format: &'static str,
+}
+
+#[derive(Deserialize)]
+struct V2Response<T> {
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct V2Translations {
+    translations: Vec<V2Translation>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct V2Translation {
+    translated_text: String,
+    detected_source_language: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct V2Languages {
+    languages: Vec<V2Language>,
+}
+
+#[derive(Deserialize)]
+struct V2Language {
+    language: String,
+    name: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct V3Request<'a> {
+    contents: [&'a str; 1],
+    target_language_code: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_language_code: Option<&'a str>,
+    mime_type: &'static str,
+}
+
+#[derive(Deserialize)]
+struct V3Response {
+    translations: Vec<V3Translation>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct V3Translation {
+    translated_text: String,
+    detected_language_code: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct V3Languages {
+    languages: Vec<V3Language>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct V3Language {
+    language_code: String,
+    display_name: Option<String>,
+    #[serde(default)]
+    support_target: bool,
+}
+
+enum Auth {
+    ApiKey(String),
+    ServiceAccount {
+        account: ServiceAccount,
+        project_id: String,
+    },
+}
+
+struct CachedToken {
+    token: String,
+    expires_at: Instant,
+}
+
+/// 访问令牌按 (client_email, token_uri) 在进程内共享，
+/// 每次翻译都会新建 `Google`，令牌不能只缓存在实例上
+static TOKENS: Lazy<Mutex<HashMap<(String, String), CachedToken>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub struct Google {
+    auth: Auth,
+    location: String,
+    client: reqwest::Client,
+}
+
+impl Google {
+    pub fn new(api_key: String, config: GoogleConfig) -> Result<Self, ProviderError> {
+        let auth = match config.version {
+            GoogleApiVersion::V2 => {
+                if api_key.trim().is_empty() {
+                    return Err(ProviderError::Config(
+                        "API Key未配置，请在系统托盘菜单中打开设置".to_string(),
+                    ));
+                }
+                Auth::ApiKey(api_key.trim().to_string())
+            }
+            GoogleApiVersion::V3 => {
+                let account = load_service_account(&config.service_account_path)?;
+                let project_id = Some(config.project_id.trim().to_string())
+                    .filter(|p| !p.is_empty())
+                    .or_else(|| account.project_id.clone())
+                    .ok_or_else(|| {
+                        ProviderError::Config("未配置 Google Cloud 项目 ID".to_string())
+                    })?;
+                Auth::ServiceAccount {
+                    account,
+                    project_id,
+                }
+            }
+        };
+
+        Ok(Self {
+            auth,
+            location: config.location,
+            client: http_client(ID)?,
+        })
+    }
+
+    /// 用服务账号私钥签发 JWT，换取 OAuth2 访问令牌，令牌在过期前复用
+    async fn access_token(&self, account: &ServiceAccount) -> Result<String, ProviderError> {
+        let cache_key = (account.client_email.clone(), account.token_uri.clone());
+        let mut tokens = TOKENS.lock().await;
+        if let Some(token) = tokens.get(&cache_key) {
+            if Instant::now() + TOKEN_REFRESH_MARGIN < token.expires_at {
+                return Ok(token.token.clone());
+            }
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let claims = JwtClaims {
+            iss: &account.client_email,
+            scope: TRANSLATION_SCOPE,
+            aud: &account.token_uri,
+            iat: now,
+            exp: now + 3600,
+        };
+        let key = EncodingKey::from_rsa_pem(account.private_key.as_bytes())
+            .map_err(|e| ProviderError::Config(format!("服务账号私钥无效: {}", e)))?;
+        let assertion = jsonwebtoken::encode(&Header::new(Algorithm::RS256), &claims, &key)
+            .map_err(|e| ProviderError::Config(format!("签发 JWT 失败: {}", e)))?;
+
+        info!("请求 Google 访问令牌: {}", account.token_uri);
+        let resp = self
+            .client
+            .post(&account.token_uri)
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                ("assertion", assertion.as_str()),
+            ])
+            .send()
+            .await?;
+
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        if !status.is_success() {
+            warn!("获取 Google 访问令牌失败: {}, 内容: {}", status, body);
+            return Err(ProviderError::Config(format!(
+                "服务账号鉴权失败 (HTTP {})",
+                status.as_u16()
+            )));
+        }
+
+        let token = serde_json::from_str::<TokenResponse>(&body)
+            .map_err(|e| ProviderError::Parse(e.to_string()))?;
+        tokens.insert(
+            cache_key,
+            CachedToken {
+                token: token.access_token.clone(),
+                expires_at: Instant::now() + Duration::from_secs(token.expires_in),
+            },
+        );
+        Ok(token.access_token)
+    }
+
+    async fn send(&self, builder: reqwest::RequestBuilder) -> Result<String, ProviderError> {
+        let builder = match &self.auth {
+            Auth::ApiKey(key) => builder.query(&[("key", key)]),
+            Auth::ServiceAccount { account, .. } => {
+                builder.bearer_auth(self.access_token(account).await?)
+            }
+        };
+
+        let resp = builder.send().await.map_err(|e| {
+            error!("API 请求失败: {}", e);
+            ProviderError::from(e)
+        })?;
+
+        let status = resp.status();
+        info!("API 响应状态码: {}", status);
+
+        let retry_after = retry_after(resp.headers());
+        let body = resp.text().await.unwrap_or_default();
+        debug!("API 响应内容: {}", body);
+
+        if !status.is_success() {
+            warn!("Google 返回非 2xx 状态码: {}, 内容: {}", status, body);
+            return Err(match status.as_u16() {
+                400 | 401 | 403 => ProviderError::Config(format!(
+                    "Google 翻译鉴权失败或请求无效 (HTTP {})",
+                    status.as_u16()
+                )),
+                429 => ProviderError::Quota("Google 翻译请求超出配额".to_string()),
+                code => ProviderError::Status(code),
+            }
+            .with_retry_after(retry_after));
+        }
+
+        Ok(body)
+    }
+
+    fn v3_url(&self, project_id: &str, method: &str) -> String {
+        format!(
+            "{}/v3/projects/{}/locations/{}{}",
+            API_BASE_URL, project_id, self.location, method
+        )
+    }
+}
+
+fn load_service_account(path: &str) -> Result<ServiceAccount, ProviderError> {
+    if path.trim().is_empty() {
+        return Err(ProviderError::Config(
+            "未配置服务账号 JSON 文件".to_string(),
+        ));
+    }
+    let raw = std::fs::read_to_string(path.trim())
+        .map_err(|e| ProviderError::Config(format!("读取服务账号文件失败: {}", e)))?;
+    serde_json::from_str(&raw)
+        .map_err(|e| ProviderError::Config(format!("服务账号文件格式错误: {}", e)))
+}
+
+/// 设置中保存的是大写代码，Google 使用 BCP-47 代码，中文需要区分简繁
+fn to_google_lang(lang: &str) -> String {
+    match lang {
+        "ZH" => "zh-CN".to_string(),
+        other if other.len() == 2 => other.to_lowercase(),
+        other => other.to_string(),
+    }
+}
+
+/// `to_google_lang` 的逆映射，`zh-TW` 等带地区的代码原样保留
+fn from_google_lang(lang: &str) -> String {
+    match lang {
+        "zh" | "zh-CN" => "ZH".to_string(),
+        other if other.len() == 2 => other.to_uppercase(),
+        other => other.to_string(),
+    }
+}
+
+fn parse<T: for<'de> Deserialize<'de>>(body: &str) -> Result<T, ProviderError> {
+    serde_json::from_str(body).map_err(|e| {
+        error!("解析 API 响应失败: {}", e);
+        error!("原始响应: {}", body);
+        ProviderError::Parse(e.to_string())
+    })
+}
+
+#[async_trait]
+impl TranslationProvider for Google {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            auto_detect: true,
+            ..Capabilities::default()
+        }
+    }
+
+    /// 语言名称使用简体中文显示，与设置页保持一致
+    async fn supported_languages(&self) -> Result<Vec<Language>, ProviderError> {
+        match &self.auth {
+            Auth::ApiKey(_) => {
+                let url = format!("{}/language/translate/v2/languages", API_BASE_URL);
+                let body = self
+                    .send(self.client.get(url).query(&[("target", "zh-CN")]))
+                    .await?;
+                let data = parse::<V2Response<V2Languages>>(&body)?;
+                Ok(data
+                    .data
+                    .languages
+                    .into_iter()
+                    .map(|l| Language {
+                        name: l.name.unwrap_or_else(|| l.language.clone()),
+                        code: from_google_lang(&l.language),
+                    })
+                    .collect())
+            }
+            Auth::ServiceAccount { project_id, .. } => {
+                let url = self.v3_url(project_id, "/supportedLanguages");
+                let body = self
+                    .send(
+                        self.client
+                            .get(url)
+                            .query(&[("displayLanguageCode", "zh-CN")]),
+                    )
+                    .await?;
+                let data = parse::<V3Languages>(&body)?;
+                Ok(data
+                    .languages
+                    .into_iter()
+                    .filter(|l| l.support_target)
+                    .map(|l| Language {
+                        name: l.display_name.unwrap_or_else(|| l.language_code.clone()),
+                        code: from_google_lang(&l.language_code),
+                    })
+                    .collect())
+            }
+        }
+    }
+
+    async fn translate(&self, req: &TranslateRequest) -> Result<Translation, ProviderError> {
+        let source =
+            Some(to_google_lang(&req.source_lang)).filter(|_| !is_auto_detect(&req.source_lang));
+        let target = to_google_lang(&req.target_lang);
+
+        match &self.auth {
+            Auth::ApiKey(_) => {
+                let url = format!("{}/language/translate/v2", API_BASE_URL);
+                info!("请求 API: {}", url);
+                let body = self
+                    .send(self.client.post(url).json(&V2Request {
+                        q: &req.text,
+                        target: &target,
+                        source: source.as_deref(),
+                        format: "text",
+                    }))
+                    .await?;
+
+                let translation = parse::<V2Response<V2Translations>>(&body)?
+                    .data
+                    .translations
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| ProviderError::Parse("响应中没有 translations".to_string()))?;
+                Ok(Translation {
+                    text: translation.translated_text,
+                    detected_source_lang: translation
+                        .detected_source_language
+                        .as_deref()
+                        .map(from_google_lang),
+                })
+            }
+            Auth::ServiceAccount { project_id, .. } => {
+                let url = self.v3_url(project_id, ":translateText");
+                info!("请求 API: {}", url);
+                let body = self
+                    .send(self.client.post(url).json(&V3Request {
+                        contents: [&req.text],
+                        target_language_code: &target,
+                        source_language_code: source.as_deref(),
+                        mime_type: "text/plain",
+                    }))
+                    .await?;
+
+                let translation = parse::<V3Response>(&body)?
+                    .translations
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| ProviderError::Parse("响应中没有 translations".to_string()))?;
+                Ok(Translation {
+                    text: translation.translated_text,
+                    detected_source_lang: translation
+                        .detected_language_code
+                        .as_deref()
+                        .map(from_google_lang),
+                })
+            }
+        }
+    }
+
+    /// 获取语言列表不计入翻译字符数，用来检查 Key 或服务账号是否可用
+    async fn validate(&self) -> Result<(), ProviderError> {
+        self.supported_languages().await.map(|_| ())
+    }
+}
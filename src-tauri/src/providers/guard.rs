@@ -0,0 +1,347 @@
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::retry::{self, RetryConfig, RetryOutcome};
+use super::{command, deepl, deeplx, google, offline, ollama, ProviderError};
+
+/// 令牌桶排队等待的上限，超过时直接提示稍后再试
+const MAX_QUEUE_WAIT: Duration = Duration::from_secs(5);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct RateLimit {
+    /// 每秒补充的请求数，0 表示不限流
+    pub rate_per_sec: f64,
+    /// 桶的容量，允许短时间内连续发出的请求数
+    pub burst: u32,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            rate_per_sec: 1.0,
+            burst: 5,
+        }
+    }
+}
+
+impl RateLimit {
+    const UNLIMITED: RateLimit = RateLimit {
+        rate_per_sec: 0.0,
+        burst: 5,
+    };
+
+    /// 内置的按服务默认值：公共 DeepLX、Google 和 DeepL 免费版共用 Key 或额度较紧，
+    /// 限流更严格以免被封禁；离线模型、Ollama 和本地命令不经过网络，不限流
+    fn builtin(id: &str) -> Option<RateLimit> {
+        match id {
+            deeplx::ID => Some(RateLimit {
+                rate_per_sec: 0.5,
+                burst: 2,
+            }),
+            google::ID => Some(RateLimit {
+                rate_per_sec: 5.0,
+                burst: 10,
+            }),
+            deepl::ID => Some(RateLimit {
+                rate_per_sec: 1.0,
+                burst: 3,
+            }),
+            command::ID | offline::ID | ollama::ID => Some(RateLimit::UNLIMITED),
+            _ => None,
+        }
+    }
+}
+
+/// 每个服务独立的限流与熔断设置
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ThrottleConfig {
+    /// 没有内置默认值的服务使用的限流
+    pub rate: RateLimit,
+    /// 按服务 ID 覆盖的限流，优先于内置默认值，例如自建 DeepLX 可以设为不限流
+    pub provider_rates: HashMap<String, RateLimit>,
+    /// 连续失败达到该次数后熔断，0 表示不熔断
+    pub failure_threshold: u32,
+    /// 熔断后等待多久放行一次探测请求
+    pub cooldown_secs: u64,
+}
+
+impl Default for ThrottleConfig {
+    fn default() -> Self {
+        Self {
+            rate: RateLimit::default(),
+            provider_rates: HashMap::new(),
+            failure_threshold: 3,
+            cooldown_secs: 60,
+        }
+    }
+}
+
+enum Circuit {
+    Closed {
+        failures: u32,
+    },
+    Open {
+        until: Instant,
+    },
+    /// 冷却结束后放行了一个探测请求，结果返回前其余请求继续被拒绝
+    HalfOpen {
+        since: Instant,
+    },
+}
+
+struct ProviderState {
+    /// 可以为负数，表示已有请求在排队等待
+    tokens: f64,
+    refilled_at: Instant,
+    circuit: Circuit,
+}
+
+impl Default for ProviderState {
+    fn default() -> Self {
+        Self {
+            tokens: f64::MAX,
+            refilled_at: Instant::now(),
+            circuit: Circuit::Closed { failures: 0 },
+        }
+    }
+}
+
+static STATES: Lazy<Mutex<HashMap<String, ProviderState>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 服务是否处于熔断中且还不能探测，备用服务链据此跳过该服务
+pub fn is_open(id: &str) -> bool {
+    let states = STATES.lock().unwrap();
+    matches!(
+        states.get(id).map(|s| &s.circuit),
+        Some(Circuit::Open { until }) if Instant::now() < *until
+    )
+}
+
+/// 检查熔断状态，冷却结束时转为半开并放行当前请求作为探测
+fn check_circuit(id: &str, config: &ThrottleConfig) -> Result<(), ProviderError> {
+    let mut states = STATES.lock().unwrap();
+    let state = states.entry(id.to_string()).or_default();
+    let now = Instant::now();
+    let cooldown = Duration::from_secs(config.cooldown_secs);
+
+    match state.circuit {
+        Circuit::Closed { .. } => Ok(()),
+        Circuit::Open { until } if now < until => {
+            Err(ProviderError::CircuitOpen(id.to_string(), until - now))
+        }
+        // 探测请求被取消时不会有结果，超过冷却时间后允许再探测一次
+        Circuit::HalfOpen { since } if now < since + cooldown => Err(ProviderError::CircuitOpen(
+            id.to_string(),
+            since + cooldown - now,
+        )),
+        Circuit::Open { .. } | Circuit::HalfOpen { .. } => {
+            info!("{} 冷却结束，放行一个探测请求", id);
+            state.circuit = Circuit::HalfOpen { since: now };
+            Ok(())
+        }
+    }
+}
+
+/// 服务实际使用的限流：用户设置 > 内置默认值 > 通用设置
+fn rate_limit(id: &str, config: &ThrottleConfig) -> RateLimit {
+    config
+        .provider_rates
+        .get(id)
+        .cloned()
+        .or_else(|| RateLimit::builtin(id))
+        .unwrap_or_else(|| config.rate.clone())
+}
+
+/// 从令牌桶取一个令牌，返回需要等待的时间
+fn take_token(id: &str, config: &ThrottleConfig) -> Result<Duration, ProviderError> {
+    let limit = rate_limit(id, config);
+    if limit.rate_per_sec <= 0.0 {
+        return Ok(Duration::ZERO);
+    }
+
+    let mut states = STATES.lock().unwrap();
+    let state = states.entry(id.to_string()).or_default();
+    let now = Instant::now();
+    let capacity = f64::from(limit.burst.max(1));
+    let elapsed = now.duration_since(state.refilled_at).as_secs_f64();
+    state.tokens = (state.tokens + elapsed * limit.rate_per_sec).min(capacity);
+    state.refilled_at = now;
+
+    state.tokens -= 1.0;
+    if state.tokens >= 0.0 {
+        return Ok(Duration::ZERO);
+    }
+
+    let wait = Duration::from_secs_f64(-state.tokens / limit.rate_per_sec);
+    if wait > MAX_QUEUE_WAIT {
+        state.tokens += 1.0;
+        return Err(ProviderError::Throttled(id.to_string(), wait));
+    }
+    Ok(wait)
+}
+
+fn record(id: &str, config: &ThrottleConfig, failed: bool) {
+    let mut states = STATES.lock().unwrap();
+    let state = states.entry(id.to_string()).or_default();
+
+    if !failed {
+        if !matches!(state.circuit, Circuit::Closed { failures: 0 }) {
+            info!("{} 已恢复", id);
+        }
+        state.circuit = Circuit::Closed { failures: 0 };
+        return;
+    }
+
+    let failures = match state.circuit {
+        Circuit::Closed { failures } => failures + 1,
+        // 探测失败时直接重新熔断
+        _ => config.failure_threshold,
+    };
+    if config.failure_threshold > 0 && failures >= config.failure_threshold {
+        warn!(
+            "{} 连续失败 {} 次，熔断 {} 秒",
+            id, failures, config.cooldown_secs
+        );
+        state.circuit = Circuit::Open {
+            until: Instant::now() + Duration::from_secs(config.cooldown_secs),
+        };
+    } else {
+        state.circuit = Circuit::Closed { failures };
+    }
+}
+
+/// 经过熔断与限流后取得发出请求的许可
+async fn acquire(id: &str, config: &ThrottleConfig) -> Result<(), ProviderError> {
+    check_circuit(id, config)?;
+    let wait = take_token(id, config)?;
+    if !wait.is_zero() {
+        info!("{} 请求过于频繁，等待 {} ms", id, wait.as_millis());
+        tokio::time::sleep(wait).await;
+    }
+    Ok(())
+}
+
+/// 按重试策略执行 `op`，每次请求前经过熔断与限流
+///
+/// 整个重试过程结束后按最后一次实际发出的请求更新熔断状态，一次失败的翻译只计一次失败，
+/// 不会因为原地重试而提前熔断。只有网络错误、429/5xx 等服务端问题才计入失败，配置错误不应导致熔断
+pub async fn run<T, F, Fut>(
+    id: &str,
+    throttle: &ThrottleConfig,
+    retry: &RetryConfig,
+    mut op: F,
+) -> RetryOutcome<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = (Result<T, ProviderError>, bool)>,
+{
+    // 最后一次发出的请求是否失败，没有发出请求时为 None
+    let last_failed = Mutex::new(None);
+    let outcome = retry::run(retry, id, || {
+        let request = op();
+        let last_failed = &last_failed;
+        async move {
+            if let Err(e) = acquire(id, throttle).await {
+                return (Err(e), true);
+            }
+            let (result, can_retry) = request.await;
+            *last_failed.lock().unwrap() = Some(matches!(&result, Err(e) if e.is_retryable()));
+            (result, can_retry)
+        }
+    })
+    .await;
+
+    if let Some(failed) = last_failed.into_inner().unwrap() {
+        record(id, throttle, failed);
+    }
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn throttle() -> ThrottleConfig {
+        ThrottleConfig {
+            rate: RateLimit::UNLIMITED,
+            ..ThrottleConfig::default()
+        }
+    }
+
+    fn retry() -> RetryConfig {
+        RetryConfig {
+            base_delay_ms: 1,
+            max_delay_ms: 1,
+            ..RetryConfig::default()
+        }
+    }
+
+    async fn failing(id: &str, calls: &AtomicU32) -> RetryOutcome<()> {
+        run(id, &throttle(), &retry(), || async {
+            calls.fetch_add(1, Ordering::Relaxed);
+            (Err(ProviderError::Status(503)), true)
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn retries_of_one_translation_count_as_one_failure() {
+        let calls = AtomicU32::new(0);
+        for translation in 1..=2 {
+            assert_eq!(failing("guard_test_retries", &calls).await.attempts, 3);
+            assert_eq!(calls.load(Ordering::Relaxed), translation * 3);
+            assert!(!is_open("guard_test_retries"));
+        }
+        failing("guard_test_retries", &calls).await;
+        assert!(is_open("guard_test_retries"));
+
+        let outcome = failing("guard_test_retries", &calls).await;
+        assert!(matches!(
+            outcome.result,
+            Err(ProviderError::CircuitOpen(..))
+        ));
+        assert_eq!(calls.load(Ordering::Relaxed), 9);
+    }
+
+    #[test]
+    fn builtin_rates_apply_unless_overridden() {
+        let mut config = ThrottleConfig::default();
+        assert_eq!(rate_limit(deeplx::ID, &config).rate_per_sec, 0.5);
+        assert_eq!(rate_limit(offline::ID, &config), RateLimit::UNLIMITED);
+        assert_eq!(rate_limit("azure", &config), RateLimit::default());
+
+        config
+            .provider_rates
+            .insert(deeplx::ID.to_string(), RateLimit::UNLIMITED);
+        config.rate.rate_per_sec = 2.0;
+        assert_eq!(rate_limit(deeplx::ID, &config), RateLimit::UNLIMITED);
+        assert_eq!(rate_limit("azure", &config).rate_per_sec, 2.0);
+        assert_eq!(rate_limit(google::ID, &config).rate_per_sec, 5.0);
+    }
+
+    #[tokio::test]
+    async fn success_resets_the_failure_count() {
+        let calls = AtomicU32::new(0);
+        for _ in 0..2 {
+            failing("guard_test_reset", &calls).await;
+        }
+        let outcome = run("guard_test_reset", &throttle(), &retry(), || async {
+            (Ok(()), true)
+        })
+        .await;
+        assert!(outcome.result.is_ok());
+        for _ in 0..2 {
+            failing("guard_test_reset", &calls).await;
+        }
+        assert!(!is_open("guard_test_reset"));
+    }
+}
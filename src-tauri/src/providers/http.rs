@@ -0,0 +1,158 @@
+use log::info;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use super::ProviderError;
+
+/// 代理设置
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProxyMode {
+    /// 使用系统代理（环境变量与系统设置）
+    #[default]
+    System,
+    /// 直连，不使用任何代理
+    None,
+    /// 使用 `proxy_url` 指定的代理
+    Manual,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+#[serde(default)]
+pub struct TimeoutConfig {
+    pub connect_secs: u64,
+    /// 两次读取之间的最长等待，流式响应不会因为总时长过长而中断
+    pub read_secs: u64,
+}
+
+impl Default for TimeoutConfig {
+    fn default() -> Self {
+        Self {
+            connect_secs: 10,
+            read_secs: 30,
+        }
+    }
+}
+
+/// 所有翻译服务共用的网络设置
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct NetworkConfig {
+    pub proxy_mode: ProxyMode,
+    /// 支持 `http://`、`https://`、`socks5://` 与 `socks5h://`
+    pub proxy_url: String,
+    pub timeouts: TimeoutConfig,
+    /// 按服务 ID 覆盖的超时，例如本地 LLM 需要更长的读取时间
+    pub provider_timeouts: HashMap<String, TimeoutConfig>,
+    /// 额外信任的根证书（PEM，可包含多个），用于企业内部的 TLS 代理
+    pub ca_cert_path: String,
+    /// 客户端证书（PEM），用于需要 mTLS 的内部网关
+    pub client_cert_path: String,
+    /// 客户端私钥（PEM），为空时从 `client_cert_path` 中读取
+    pub client_key_path: String,
+}
+
+struct HttpState {
+    config: NetworkConfig,
+    /// 按超时设置缓存的客户端，同一设置的服务共用连接池
+    clients: HashMap<TimeoutConfig, reqwest::Client>,
+}
+
+static STATE: Lazy<RwLock<HttpState>> = Lazy::new(|| {
+    RwLock::new(HttpState {
+        config: NetworkConfig::default(),
+        clients: HashMap::new(),
+    })
+});
+
+fn config_error(e: impl std::fmt::Display) -> ProviderError {
+    ProviderError::Config(format!("HTTP 客户端初始化失败: {}", e))
+}
+
+fn read_file(kind: &str, path: &str) -> Result<Vec<u8>, ProviderError> {
+    fs::read(path.trim()).map_err(|e| config_error(format!("读取{} {} 失败: {}", kind, path, e)))
+}
+
+fn build(
+    config: &NetworkConfig,
+    timeouts: &TimeoutConfig,
+) -> Result<reqwest::Client, ProviderError> {
+    let mut builder = reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(timeouts.connect_secs.max(1)))
+        .read_timeout(Duration::from_secs(timeouts.read_secs.max(1)));
+
+    builder = match config.proxy_mode {
+        // reqwest 默认读取系统代理
+        ProxyMode::System => builder,
+        ProxyMode::None => builder.no_proxy(),
+        ProxyMode::Manual => {
+            let proxy = reqwest::Proxy::all(config.proxy_url.trim())
+                .map_err(|e| config_error(format!("代理地址 {} 无效: {}", config.proxy_url, e)))?;
+            builder.proxy(proxy)
+        }
+    };
+
+    if !config.ca_cert_path.trim().is_empty() {
+        let pem = read_file("根证书", &config.ca_cert_path)?;
+        let certs = reqwest::Certificate::from_pem_bundle(&pem).map_err(config_error)?;
+        builder = builder.tls_certs_merge(certs);
+    }
+
+    if !config.client_cert_path.trim().is_empty() {
+        let mut pem = read_file("客户端证书", &config.client_cert_path)?;
+        if !config.client_key_path.trim().is_empty() {
+            pem.push(b'\n');
+            pem.extend(read_file("客户端私钥", &config.client_key_path)?);
+        }
+        let identity = reqwest::Identity::from_pem(&pem).map_err(config_error)?;
+        builder = builder.identity(identity);
+    }
+
+    builder.build().map_err(config_error)
+}
+
+/// 应用新的网络设置，设置有变化时丢弃已创建的客户端
+///
+/// 会先用新设置创建一次客户端，证书或代理地址有误时返回错误并保留原设置
+pub fn configure(config: &NetworkConfig) -> Result<(), ProviderError> {
+    if STATE.read().unwrap().config == *config {
+        return Ok(());
+    }
+
+    let client = build(config, &config.timeouts)?;
+    let mut state = STATE.write().unwrap();
+    state.config = config.clone();
+    state.clients.clear();
+    state.clients.insert(config.timeouts.clone(), client);
+    info!("已更新网络设置: 代理 {:?}", config.proxy_mode);
+    Ok(())
+}
+
+/// 获取指定服务使用的客户端，设置相同的服务共用同一个连接池
+pub(crate) fn http_client(provider: &str) -> Result<reqwest::Client, ProviderError> {
+    let (config, timeouts) = {
+        let state = STATE.read().unwrap();
+        let timeouts = state
+            .config
+            .provider_timeouts
+            .get(provider)
+            .unwrap_or(&state.config.timeouts)
+            .clone();
+        if let Some(client) = state.clients.get(&timeouts) {
+            return Ok(client.clone());
+        }
+        (state.config.clone(), timeouts)
+    };
+
+    let client = build(&config, &timeouts)?;
+    let mut state = STATE.write().unwrap();
+    // 构建期间设置可能已被更新，此时不缓存旧设置创建的客户端
+    if state.config == config {
+        state.clients.insert(timeouts, client.clone());
+    }
+    Ok(client)
+}
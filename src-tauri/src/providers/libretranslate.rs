@@ -0,0 +1,256 @@
+use async_trait::async_trait;
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+
+use super::retry::retry_after;
+use super::{
+    http_client, is_auto_detect, Capabilities, Language, ProviderError, TranslateRequest,
+    Translation, TranslationProvider,
+};
+
+pub const ID: &str = "libretranslate";
+
+const DEFAULT_BASE_URL: &str = "http://localhost:5000";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct LibreTranslateConfig {
+    /// 该服务专用的 API Key，为空时使用通用的 `api_key`
+    pub api_key: String,
+    pub base_url: String,
+}
+
+impl Default for LibreTranslateConfig {
+    fn default() -> Self {
+        Self {
+            api_key: String::new(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct LibreRequest<'a> {
+    q: &'a str,
+    source: &'a str,
+    target: &'a str,
+    format: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct DetectRequest<'a> {
+    q: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+struct LibreResponse {
+    #[serde(rename = "translatedText")]
+    translated_text: String,
+}
+
+#[derive(Deserialize)]
+struct DetectResponse {
+    language: String,
+    confidence: f64,
+}
+
+#[derive(Deserialize)]
+struct LanguageResponse {
+    code: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+pub struct LibreTranslate {
+    api_key: String,
+    config: LibreTranslateConfig,
+    client: reqwest::Client,
+}
+
+impl LibreTranslate {
+    pub fn new(api_key: String, config: LibreTranslateConfig) -> Result<Self, ProviderError> {
+        if !config.base_url.starts_with("http://") && !config.base_url.starts_with("https://") {
+            return Err(ProviderError::Config(format!(
+                "LibreTranslate 服务地址无效: {}",
+                config.base_url
+            )));
+        }
+        Ok(Self {
+            api_key,
+            config,
+            client: http_client(ID)?,
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.config.base_url.trim_end_matches('/'), path)
+    }
+
+    /// 未开启 `--api-keys` 的实例不需要 Key
+    fn api_key(&self) -> Option<&str> {
+        Some(self.api_key.trim()).filter(|k| !k.is_empty())
+    }
+
+    async fn post<T: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        body: &T,
+    ) -> Result<String, ProviderError> {
+        let url = self.url(path);
+        info!("请求 API: {}", url);
+
+        let resp = self
+            .client
+            .post(&url)
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| {
+                error!("API 请求失败: {}", e);
+                ProviderError::from(e)
+            })?;
+
+        let status = resp.status();
+        info!("API 响应状态码: {}", status);
+        let retry_after = retry_after(resp.headers());
+
+        let body = resp.text().await.unwrap_or_default();
+        debug!("API 响应内容: {}", body);
+
+        if !status.is_success() {
+            let message = serde_json::from_str::<ErrorResponse>(&body)
+                .map(|e| e.error)
+                .unwrap_or_default();
+            warn!("LibreTranslate 返回状态码: {}, 错误: {}", status, message);
+            return Err(match status.as_u16() {
+                400 | 403 if !message.is_empty() => {
+                    ProviderError::Config(format!("LibreTranslate: {}", message))
+                }
+                code => ProviderError::Status(code),
+            }
+            .with_retry_after(retry_after));
+        }
+
+        Ok(body)
+    }
+
+    /// 调用 /detect，返回置信度最高的语言代码
+    async fn detect(&self, text: &str) -> Result<String, ProviderError> {
+        let body = self
+            .post(
+                "/detect",
+                &DetectRequest {
+                    q: text,
+                    api_key: self.api_key(),
+                },
+            )
+            .await?;
+
+        let candidates = serde_json::from_str::<Vec<DetectResponse>>(&body).map_err(|e| {
+            error!("解析语言检测结果失败: {}", e);
+            ProviderError::Parse(e.to_string())
+        })?;
+
+        let best = candidates
+            .into_iter()
+            .max_by(|a, b| a.confidence.total_cmp(&b.confidence))
+            .ok_or_else(|| ProviderError::Parse("无法识别源语言".to_string()))?;
+
+        info!(
+            "检测到源语言: {} (置信度 {})",
+            best.language, best.confidence
+        );
+        Ok(best.language)
+    }
+}
+
+/// LibreTranslate 使用小写的 ISO 639 代码，设置中保存的是大写代码
+fn to_libre_lang(lang: &str) -> String {
+    lang.to_lowercase()
+}
+
+fn from_libre_lang(lang: &str) -> String {
+    lang.to_uppercase()
+}
+
+#[async_trait]
+impl TranslationProvider for LibreTranslate {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            auto_detect: true,
+            ..Capabilities::default()
+        }
+    }
+
+    async fn supported_languages(&self) -> Result<Vec<Language>, ProviderError> {
+        let resp = self.client.get(self.url("/languages")).send().await?;
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(ProviderError::Status(status.as_u16()));
+        }
+
+        let body = resp.text().await.unwrap_or_default();
+        let languages = serde_json::from_str::<Vec<LanguageResponse>>(&body)
+            .map_err(|e| ProviderError::Parse(e.to_string()))?;
+
+        Ok(languages
+            .into_iter()
+            .map(|l| Language {
+                code: from_libre_lang(&l.code),
+                name: l.name,
+            })
+            .collect())
+    }
+
+    async fn translate(&self, req: &TranslateRequest) -> Result<Translation, ProviderError> {
+        // 先检测语言，这样弹窗可以显示识别结果
+        let (source, detected_source_lang) = if is_auto_detect(&req.source_lang) {
+            let detected = self.detect(&req.text).await?;
+            let display = from_libre_lang(&detected);
+            (detected, Some(display))
+        } else {
+            (to_libre_lang(&req.source_lang), None)
+        };
+
+        let body = self
+            .post(
+                "/translate",
+                &LibreRequest {
+                    q: &req.text,
+                    source: &source,
+                    target: &to_libre_lang(&req.target_lang),
+                    format: "text",
+                    api_key: self.api_key(),
+                },
+            )
+            .await?;
+
+        let data = serde_json::from_str::<LibreResponse>(&body).map_err(|e| {
+            error!("解析 API 响应失败: {}", e);
+            error!("原始响应: {}", body);
+            ProviderError::Parse(e.to_string())
+        })?;
+
+        Ok(Translation {
+            text: data.translated_text,
+            detected_source_lang,
+        })
+    }
+
+    async fn validate(&self) -> Result<(), ProviderError> {
+        // /detect 需要 Key（如果实例开启了鉴权），且不消耗翻译资源
+        self.detect("hello").await.map(|_| ())
+    }
+}
@@ -0,0 +1,350 @@
+pub mod aws;
+pub mod azure;
+pub mod baidu;
+pub mod command;
+pub mod custom;
+pub mod deepl;
+pub mod deeplx;
+pub mod fallback;
+pub mod google;
+pub mod guard;
+pub mod http;
+pub mod libretranslate;
+pub mod offline;
+pub mod ollama;
+pub mod openai;
+mod prompt;
+pub mod retry;
+mod signing;
+mod stream;
+mod template;
+pub mod tencent;
+pub mod youdao;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+use crate::AppSettings;
+pub(crate) use http::http_client;
+
+pub const DEFAULT_PROVIDER: &str = "deeplx";
+
+/// `source_lang` 取该值时由翻译服务自动检测源语言
+pub const AUTO_DETECT: &str = "auto";
+
+pub(crate) fn is_auto_detect(lang: &str) -> bool {
+    lang.is_empty() || lang.eq_ignore_ascii_case(AUTO_DETECT)
+}
+
+/// 提交给翻译引擎的请求，各 Provider 自行转换为对应的接口格式
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TranslateRequest {
+    pub text: String,
+    pub source_lang: String,
+    pub target_lang: String,
+}
+
+/// 流式翻译时接收增量文本的回调
+pub type ChunkSink<'a> = &'a (dyn Fn(&str) + Send + Sync);
+
+/// 翻译引擎返回的结果
+#[derive(Clone, Debug)]
+pub struct Translation {
+    pub text: String,
+    /// 源语言为自动检测时，服务识别出的语言
+    pub detected_source_lang: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Language {
+    pub code: String,
+    pub name: String,
+}
+
+/// 翻译引擎支持的能力，供前端决定显示哪些选项
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct Capabilities {
+    pub auto_detect: bool,
+    pub streaming: bool,
+    pub usage: bool,
+    /// 单词可查询词典释义
+    pub dictionary: bool,
+}
+
+/// 词典查询返回的一个释义
+#[derive(Serialize, Clone, Debug)]
+pub struct DictionaryEntry {
+    /// 目标语言中的译法
+    pub translation: String,
+    /// 词性，如 NOUN、VERB
+    pub pos: String,
+    /// 译法对应回源语言的常见单词
+    pub back_translations: Vec<String>,
+}
+
+/// 选中的文本是否为单个单词，只有单词才查询词典
+pub(crate) fn is_single_word(text: &str) -> bool {
+    let word = text.trim();
+    !word.is_empty()
+        && word.chars().count() <= 32
+        && !word.chars().any(char::is_whitespace)
+        && word.chars().any(char::is_alphabetic)
+}
+
+/// 按字符计费的翻译服务的用量
+#[derive(Serialize, Clone, Debug)]
+pub struct Usage {
+    pub character_count: u64,
+    pub character_limit: u64,
+}
+
+#[derive(Debug, Clone)]
+pub enum ProviderError {
+    /// 配置缺失或无效（如 API Key 未填写）
+    Config(String),
+    /// 网络请求失败（连接失败、超时等）
+    Network(String),
+    /// HTTP 状态码非 2xx
+    Status(u16),
+    /// 翻译服务返回了业务错误码
+    Api(i64),
+    /// 额度用尽或被限流
+    Quota(String),
+    /// 响应内容无法解析
+    Parse(String),
+    /// 服务通过 Retry-After 要求等待后再试
+    RetryAfter(Box<ProviderError>, Duration),
+    /// 客户端限流，排队时间过长，没有发出请求
+    Throttled(String, Duration),
+    /// 连续失败后熔断，冷却期间不发出请求
+    CircuitOpen(String, Duration),
+}
+
+impl fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProviderError::Config(msg) => write!(f, "{}", msg),
+            ProviderError::Network(msg) => write!(f, "网络连接失败: {}", msg),
+            ProviderError::Status(code) => write!(f, "翻译服务返回状态码: {}", code),
+            ProviderError::Api(code) => write!(f, "翻译服务返回错误码: {}", code),
+            ProviderError::Quota(msg) => write!(f, "{}", msg),
+            ProviderError::Parse(_) => write!(f, "翻译服务响应格式错误"),
+            ProviderError::RetryAfter(inner, _) => write!(f, "{}", inner),
+            ProviderError::Throttled(id, after) => {
+                write!(
+                    f,
+                    "翻译服务 {} 请求过于频繁，{} 秒后重试",
+                    id,
+                    ceil_secs(*after)
+                )
+            }
+            ProviderError::CircuitOpen(id, after) => {
+                write!(
+                    f,
+                    "翻译服务 {} 暂时停用，{} 秒后重试",
+                    id,
+                    ceil_secs(*after)
+                )
+            }
+        }
+    }
+}
+
+impl ProviderError {
+    /// 是否值得换一个服务或稍后再试：网络错误、429/5xx、额度用尽
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ProviderError::Network(_) | ProviderError::Quota(_) => true,
+            ProviderError::Status(code) => *code == 429 || *code >= 500,
+            // DeepLX 把上游的状态码放在响应体的 code 字段中
+            ProviderError::Api(code) => *code == 429 || *code == 456 || *code >= 500,
+            ProviderError::Config(_) | ProviderError::Parse(_) => false,
+            ProviderError::RetryAfter(..)
+            | ProviderError::Throttled(..)
+            | ProviderError::CircuitOpen(..) => true,
+        }
+    }
+
+    /// 是否值得在同一服务上等待后重试
+    ///
+    /// 额度用尽时重试同一服务没有意义，直接切换备用服务；服务通过 Retry-After
+    /// 明确要求等待的除外。客户端主动拒绝、没有发出请求的错误同样只切换服务
+    pub fn should_retry_in_place(&self) -> bool {
+        match self {
+            ProviderError::RetryAfter(..) => true,
+            ProviderError::Quota(_) | ProviderError::Api(456) => false,
+            ProviderError::Throttled(..) | ProviderError::CircuitOpen(..) => false,
+            other => other.is_retryable(),
+        }
+    }
+
+    /// 服务要求的等待时间
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            ProviderError::RetryAfter(_, after) => Some(*after),
+            _ => None,
+        }
+    }
+
+    /// 附加响应中的 Retry-After，只有可重试的错误才会保留
+    pub(crate) fn with_retry_after(self, after: Option<Duration>) -> Self {
+        match after {
+            Some(after) if self.is_retryable() => ProviderError::RetryAfter(Box::new(self), after),
+            _ => self,
+        }
+    }
+}
+
+fn ceil_secs(duration: Duration) -> u64 {
+    duration.as_millis().div_ceil(1000) as u64
+}
+
+impl From<reqwest::Error> for ProviderError {
+    fn from(e: reqwest::Error) -> Self {
+        ProviderError::Network(e.to_string())
+    }
+}
+
+#[async_trait]
+pub trait TranslationProvider: Send + Sync {
+    /// Provider 标识，与 `AppSettings.provider` 对应
+    fn id(&self) -> &'static str;
+
+    fn capabilities(&self) -> Capabilities;
+
+    async fn supported_languages(&self) -> Result<Vec<Language>, ProviderError>;
+
+    async fn translate(&self, req: &TranslateRequest) -> Result<Translation, ProviderError>;
+
+    /// 流式翻译，每收到一段增量文本调用一次 `on_chunk`，最后返回完整结果
+    ///
+    /// 不支持流式的 Provider 使用默认实现：整段翻译完成后作为单个分片发出
+    async fn translate_stream(
+        &self,
+        req: &TranslateRequest,
+        on_chunk: ChunkSink<'_>,
+    ) -> Result<Translation, ProviderError> {
+        let translation = self.translate(req).await?;
+        on_chunk(&translation.text);
+        Ok(translation)
+    }
+
+    /// 用当前配置发起一次轻量请求，检查凭据与连通性
+    async fn validate(&self) -> Result<(), ProviderError>;
+
+    /// 查询用量，仅 `Capabilities.usage` 为 true 的 Provider 需要实现
+    async fn usage(&self) -> Result<Usage, ProviderError> {
+        Err(ProviderError::Config(
+            "当前翻译服务不支持用量查询".to_string(),
+        ))
+    }
+
+    /// 查询单词的其他译法，仅 `Capabilities.dictionary` 为 true 的 Provider 需要实现
+    async fn lookup(&self, _req: &TranslateRequest) -> Result<Vec<DictionaryEntry>, ProviderError> {
+        Err(ProviderError::Config(
+            "当前翻译服务不支持词典查询".to_string(),
+        ))
+    }
+}
+
+/// 根据设置创建指定的翻译引擎，`id` 与 `AppSettings.provider` 取值相同
+pub fn build_provider(
+    id: &str,
+    settings: &AppSettings,
+) -> Result<Box<dyn TranslationProvider>, ProviderError> {
+    match id {
+        aws::ID => Ok(Box::new(aws::Aws::new(settings.aws.clone())?)),
+        azure::ID => Ok(Box::new(azure::Azure::new(
+            provider_key(&settings.azure.api_key, settings),
+            settings.azure.clone(),
+        )?)),
+        baidu::ID => Ok(Box::new(baidu::Baidu::new(settings.baidu.clone())?)),
+        command::ID => Ok(Box::new(command::ExternalCommand::new(
+            settings.command.clone(),
+        )?)),
+        custom::ID => Ok(Box::new(custom::Custom::new(
+            provider_key(&settings.custom.api_key, settings),
+            settings.custom.clone(),
+        )?)),
+        deeplx::ID => Ok(Box::new(deeplx::DeepLx::new(
+            provider_key(&settings.deeplx.api_key, settings),
+            settings.deeplx.clone(),
+        )?)),
+        deepl::ID => Ok(Box::new(deepl::DeepL::new(
+            provider_key(&settings.deepl.api_key, settings),
+            settings.deepl.clone(),
+        )?)),
+        google::ID => Ok(Box::new(google::Google::new(
+            provider_key(&settings.google.api_key, settings),
+            settings.google.clone(),
+        )?)),
+        libretranslate::ID => Ok(Box::new(libretranslate::LibreTranslate::new(
+            provider_key(&settings.libretranslate.api_key, settings),
+            settings.libretranslate.clone(),
+        )?)),
+        offline::ID => Ok(Box::new(offline::Offline::new(&settings.offline)?)),
+        ollama::ID => Ok(Box::new(ollama::Ollama::new(settings.ollama.clone())?)),
+        openai::ID => Ok(Box::new(openai::OpenAi::new(
+            provider_key(&settings.openai.api_key, settings),
+            settings.openai.clone(),
+        )?)),
+        tencent::ID => Ok(Box::new(tencent::Tencent::new(settings.tencent.clone())?)),
+        youdao::ID => Ok(Box::new(youdao::Youdao::new(settings.youdao.clone())?)),
+        other => Err(ProviderError::Config(format!(
+            "不支持的翻译服务: {}",
+            other
+        ))),
+    }
+}
+
+/// 用指定的服务翻译，按 `AppSettings.retry` 重试，不经过备用服务链
+pub async fn translate_with(
+    id: &str,
+    settings: &AppSettings,
+    req: &TranslateRequest,
+) -> Result<Translation, ProviderError> {
+    let provider = build_provider(id, settings)?;
+    let provider = &provider;
+    guard::run(id, &settings.throttle, &settings.retry, || async move {
+        (provider.translate(req).await, true)
+    })
+    .await
+    .result
+}
+
+/// 服务专用的 Key 优先，否则使用设置页中通用的 API Key
+fn provider_key(own: &str, settings: &AppSettings) -> String {
+    if own.trim().is_empty() {
+        settings.api_key.clone()
+    } else {
+        own.to_string()
+    }
+}
+
+/// DeepLX 与前端设置页共用的 14 种语言
+pub(crate) fn default_languages() -> Vec<Language> {
+    [
+        ("EN", "English"),
+        ("ZH", "简体中文"),
+        ("JA", "日本語"),
+        ("KO", "한국어"),
+        ("FR", "Français"),
+        ("DE", "Deutsch"),
+        ("ES", "Español"),
+        ("RU", "Русский"),
+        ("IT", "Italiano"),
+        ("PT", "Português"),
+        ("AR", "العربية"),
+        ("NL", "Nederlands"),
+        ("PL", "Polski"),
+        ("TR", "Türkçe"),
+    ]
+    .iter()
+    .map(|(code, name)| Language {
+        code: code.to_string(),
+        name: name.to_string(),
+    })
+    .collect()
+}
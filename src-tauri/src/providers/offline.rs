@@ -0,0 +1,401 @@
+use async_trait::async_trait;
+use candle_core::{DType, Device, Tensor};
+use candle_nn::VarBuilder;
+use candle_transformers::generation::LogitsProcessor;
+use candle_transformers::models::marian;
+use log::{info, warn};
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokenizers::Tokenizer;
+
+use super::{
+    default_languages, is_auto_detect, Capabilities, Language, ProviderError, TranslateRequest,
+    Translation, TranslationProvider,
+};
+
+pub const ID: &str = "offline";
+
+// 每个语言对一个目录，目录名为 `{源语言}-{目标语言}`（小写），例如 `en-zh`，
+// 目录内需包含 Opus-MT 模型转换为 safetensors 与 tokenizers 格式后的以下文件
+const CONFIG_FILE: &str = "config.json";
+const WEIGHTS_FILE: &str = "model.safetensors";
+const SOURCE_TOKENIZER_FILE: &str = "tokenizer-source.json";
+const TARGET_TOKENIZER_FILE: &str = "tokenizer-target.json";
+const REQUIRED_FILES: [&str; 4] = [
+    CONFIG_FILE,
+    WEIGHTS_FILE,
+    SOURCE_TOKENIZER_FILE,
+    TARGET_TOKENIZER_FILE,
+];
+
+/// 单句最多生成的 token 数，防止模型不输出结束符时无限循环
+const MAX_DECODE_TOKENS: usize = 512;
+
+/// 应用数据目录下的默认模型目录，启动时由 `run()` 设置
+pub static DEFAULT_MODELS_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+/// 已加载的模型，加载一次约需数秒，按目录缓存
+static LOADED_MODELS: Lazy<Mutex<HashMap<PathBuf, Arc<Mutex<MarianModel>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct OfflineConfig {
+    /// 模型所在目录，为空时使用应用数据目录下的 `models`
+    pub models_dir: String,
+}
+
+/// 已安装的离线模型
+#[derive(Serialize, Clone, Debug)]
+pub struct InstalledModel {
+    pub source_lang: String,
+    pub target_lang: String,
+    pub path: String,
+}
+
+pub fn models_dir(config: &OfflineConfig) -> Result<PathBuf, ProviderError> {
+    if !config.models_dir.trim().is_empty() {
+        return Ok(PathBuf::from(config.models_dir.trim()));
+    }
+    DEFAULT_MODELS_DIR
+        .get()
+        .cloned()
+        .ok_or_else(|| ProviderError::Config("未找到离线模型目录".to_string()))
+}
+
+fn is_lang_code(lang: &str) -> bool {
+    !lang.is_empty() && lang.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+/// 语言对的目录名；语言代码只能由字母组成，既避免拼出模型目录之外的路径，
+/// 也保证目录名能按 `-` 拆回原来的语言对
+fn pair_name(source_lang: &str, target_lang: &str) -> Result<String, ProviderError> {
+    for lang in [source_lang, target_lang] {
+        if !is_lang_code(lang) {
+            return Err(ProviderError::Config(format!("无效的语言代码: {}", lang)));
+        }
+    }
+    Ok(format!(
+        "{}-{}",
+        source_lang.to_lowercase(),
+        target_lang.to_lowercase()
+    ))
+}
+
+/// `pair_name` 的逆运算，返回大写的源语言与目标语言
+fn parse_pair_name(name: &str) -> Option<(String, String)> {
+    let (source, target) = name.split_once('-')?;
+    if !is_lang_code(source) || !is_lang_code(target) {
+        return None;
+    }
+    Some((source.to_uppercase(), target.to_uppercase()))
+}
+
+fn missing_files(dir: &Path) -> Vec<&'static str> {
+    REQUIRED_FILES
+        .iter()
+        .copied()
+        .filter(|f| !dir.join(f).is_file())
+        .collect()
+}
+
+/// 列出模型目录下所有文件完整的语言对
+pub fn list_models(dir: &Path) -> Result<Vec<InstalledModel>, ProviderError> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries =
+        fs::read_dir(dir).map_err(|e| ProviderError::Config(format!("读取模型目录失败: {}", e)))?;
+
+    let mut models = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some((source_lang, target_lang)) = parse_pair_name(&name) else {
+            continue;
+        };
+        let missing = missing_files(&path);
+        if !missing.is_empty() {
+            warn!("离线模型 {} 缺少文件: {:?}", name, missing);
+            continue;
+        }
+        models.push(InstalledModel {
+            source_lang,
+            target_lang,
+            path: path.to_string_lossy().to_string(),
+        });
+    }
+    models.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(models)
+}
+
+/// 从 `from` 目录复制模型文件，安装为 `source_lang` -> `target_lang` 的语言对
+pub fn import_model(
+    dir: &Path,
+    from: &Path,
+    source_lang: &str,
+    target_lang: &str,
+) -> Result<InstalledModel, ProviderError> {
+    let dest = dir.join(pair_name(source_lang, target_lang)?);
+    let missing = missing_files(from);
+    if !missing.is_empty() {
+        return Err(ProviderError::Config(format!(
+            "模型目录缺少文件: {}",
+            missing.join(", ")
+        )));
+    }
+    // 先读一遍配置，避免导入无法加载的模型
+    read_config(from)?;
+
+    fs::create_dir_all(&dest)
+        .map_err(|e| ProviderError::Config(format!("创建模型目录失败: {}", e)))?;
+    for file in REQUIRED_FILES {
+        fs::copy(from.join(file), dest.join(file))
+            .map_err(|e| ProviderError::Config(format!("复制 {} 失败: {}", file, e)))?;
+    }
+
+    // 覆盖安装时丢弃已加载的旧模型
+    LOADED_MODELS.lock().unwrap().remove(&dest);
+
+    info!("已导入离线模型: {}", dest.display());
+    Ok(InstalledModel {
+        source_lang: source_lang.to_uppercase(),
+        target_lang: target_lang.to_uppercase(),
+        path: dest.to_string_lossy().to_string(),
+    })
+}
+
+fn read_config(dir: &Path) -> Result<marian::Config, ProviderError> {
+    let raw = fs::read_to_string(dir.join(CONFIG_FILE))
+        .map_err(|e| ProviderError::Config(format!("读取 {} 失败: {}", CONFIG_FILE, e)))?;
+    serde_json::from_str(&raw)
+        .map_err(|e| ProviderError::Config(format!("{} 格式错误: {}", CONFIG_FILE, e)))
+}
+
+fn inference_error(e: impl std::fmt::Display) -> ProviderError {
+    ProviderError::Config(format!("离线模型推理失败: {}", e))
+}
+
+struct MarianModel {
+    model: marian::MTModel,
+    config: marian::Config,
+    source_tokenizer: Tokenizer,
+    target_tokenizer: Tokenizer,
+    device: Device,
+}
+
+impl MarianModel {
+    fn load(dir: &Path) -> Result<Self, ProviderError> {
+        info!("加载离线模型: {}", dir.display());
+        let config = read_config(dir)?;
+        let load_tokenizer = |file: &str| {
+            Tokenizer::from_file(dir.join(file))
+                .map_err(|e| ProviderError::Config(format!("加载 {} 失败: {}", file, e)))
+        };
+        let source_tokenizer = load_tokenizer(SOURCE_TOKENIZER_FILE)?;
+        let target_tokenizer = load_tokenizer(TARGET_TOKENIZER_FILE)?;
+
+        let device = Device::Cpu;
+        // SAFETY: 模型文件只读映射，加载期间不会被修改
+        let vb = unsafe {
+            VarBuilder::from_mmaped_safetensors(&[dir.join(WEIGHTS_FILE)], DType::F32, &device)
+        }
+        .map_err(inference_error)?;
+        let model = marian::MTModel::new(&config, vb).map_err(inference_error)?;
+
+        Ok(Self {
+            model,
+            config,
+            source_tokenizer,
+            target_tokenizer,
+            device,
+        })
+    }
+
+    /// 贪心解码翻译一句话
+    fn translate_sentence(&mut self, text: &str) -> Result<String, ProviderError> {
+        self.model.reset_kv_cache();
+        // 温度为 None 时 LogitsProcessor 退化为 argmax，种子不影响结果
+        let mut logits_processor = LogitsProcessor::new(0, None, None);
+
+        let mut tokens = self
+            .source_tokenizer
+            .encode(text, true)
+            .map_err(inference_error)?
+            .get_ids()
+            .to_vec();
+        tokens.push(self.config.eos_token_id);
+        let tokens = Tensor::new(tokens.as_slice(), &self.device)
+            .and_then(|t| t.unsqueeze(0))
+            .map_err(inference_error)?;
+        let encoder_xs = self
+            .model
+            .encoder()
+            .forward(&tokens, 0)
+            .map_err(inference_error)?;
+
+        let mut token_ids = vec![self.config.decoder_start_token_id];
+        for index in 0..MAX_DECODE_TOKENS {
+            // 有 KV 缓存，第一步之后每次只需输入最新的 token
+            let context_size = if index >= 1 { 1 } else { token_ids.len() };
+            let start_pos = token_ids.len().saturating_sub(context_size);
+            let token = Tensor::new(&token_ids[start_pos..], &self.device)
+                .and_then(|t| t.unsqueeze(0))
+                .and_then(|input| self.model.decode(&input, &encoder_xs, start_pos))
+                .and_then(|logits| logits.squeeze(0))
+                .and_then(|logits| logits.get(logits.dim(0)? - 1))
+                .and_then(|logits| logits_processor.sample(&logits))
+                .map_err(inference_error)?;
+
+            if token == self.config.eos_token_id || token == self.config.forced_eos_token_id {
+                break;
+            }
+            token_ids.push(token);
+        }
+
+        self.target_tokenizer
+            .decode(&token_ids[1..], true)
+            .map_err(inference_error)
+    }
+}
+
+pub struct Offline {
+    dir: PathBuf,
+}
+
+impl Offline {
+    pub fn new(config: &OfflineConfig) -> Result<Self, ProviderError> {
+        Ok(Self {
+            dir: models_dir(config)?,
+        })
+    }
+
+    fn model_for(&self, req: &TranslateRequest) -> Result<Arc<Mutex<MarianModel>>, ProviderError> {
+        if is_auto_detect(&req.source_lang) {
+            return Err(ProviderError::Config(
+                "离线翻译不支持自动检测源语言，请在设置中选择源语言".to_string(),
+            ));
+        }
+
+        let dir = self
+            .dir
+            .join(pair_name(&req.source_lang, &req.target_lang)?);
+        if !missing_files(&dir).is_empty() {
+            return Err(ProviderError::Config(format!(
+                "未安装 {} -> {} 的离线模型",
+                req.source_lang, req.target_lang
+            )));
+        }
+
+        let cached = LOADED_MODELS.lock().unwrap().get(&dir).cloned();
+        if let Some(model) = cached {
+            return Ok(model);
+        }
+        // 加载需要数秒，不持有锁，以免阻塞其他语言对的翻译；同时加载时保留先完成的那个
+        let model = Arc::new(Mutex::new(MarianModel::load(&dir)?));
+        Ok(LOADED_MODELS
+            .lock()
+            .unwrap()
+            .entry(dir)
+            .or_insert(model)
+            .clone())
+    }
+}
+
+#[async_trait]
+impl TranslationProvider for Offline {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+
+    /// 只返回已安装模型涉及的语言
+    async fn supported_languages(&self) -> Result<Vec<Language>, ProviderError> {
+        let installed = list_models(&self.dir)?;
+        Ok(default_languages()
+            .into_iter()
+            .filter(|l| {
+                installed
+                    .iter()
+                    .any(|m| m.source_lang == l.code || m.target_lang == l.code)
+            })
+            .collect())
+    }
+
+    async fn translate(&self, req: &TranslateRequest) -> Result<Translation, ProviderError> {
+        let dir = self.dir.clone();
+        let req = req.clone();
+
+        // 模型加载与推理都是 CPU 密集操作，不能阻塞异步运行时
+        let text = tokio::task::spawn_blocking(move || {
+            let model = Offline { dir }.model_for(&req)?;
+            let mut model = model.lock().unwrap();
+
+            // Marian 按句训练，逐行翻译并保留原文的换行
+            req.text
+                .lines()
+                .map(|line| {
+                    if line.trim().is_empty() {
+                        Ok(String::new())
+                    } else {
+                        model.translate_sentence(line.trim())
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(|lines| lines.join("\n"))
+        })
+        .await
+        .map_err(inference_error)??;
+
+        Ok(Translation {
+            text,
+            detected_source_lang: None,
+        })
+    }
+
+    async fn validate(&self) -> Result<(), ProviderError> {
+        if list_models(&self.dir)?.is_empty() {
+            return Err(ProviderError::Config(format!(
+                "{} 中没有可用的离线模型",
+                self.dir.display()
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pair_names_round_trip() {
+        let name = pair_name("EN", "ZH").unwrap();
+        assert_eq!(name, "en-zh");
+        assert_eq!(
+            parse_pair_name(&name),
+            Some(("EN".to_string(), "ZH".to_string()))
+        );
+    }
+
+    #[test]
+    fn pair_names_reject_ambiguous_or_unsafe_codes() {
+        for (source, target) in [("zh-TW", "EN"), ("EN", ""), ("..", "ZH"), ("EN", "zh/../x")] {
+            assert!(pair_name(source, target).is_err(), "{} {}", source, target);
+        }
+        for name in ["zh-tw-en", "en", "en-", "-zh", "en_zh", "opus-mt-en-zh"] {
+            assert_eq!(parse_pair_name(name), None, "{}", name);
+        }
+    }
+}
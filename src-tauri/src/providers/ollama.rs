@@ -0,0 +1,348 @@
+use async_trait::async_trait;
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+
+use super::prompt::{self, DEFAULT_SYSTEM_PROMPT, DEFAULT_USER_PROMPT};
+use super::retry::retry_after;
+use super::stream::LineReader;
+use super::{
+    default_languages, http_client, Capabilities, ChunkSink, Language, ProviderError,
+    TranslateRequest, Translation, TranslationProvider,
+};
+
+pub const ID: &str = "ollama";
+
+const DEFAULT_BASE_URL: &str = "http://localhost:11434";
+
+/// 使用的 Ollama 接口
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OllamaApi {
+    /// `/api/chat`，system 与 user 提示词分开发送
+    #[default]
+    Chat,
+    /// `/api/generate`，适合没有 chat 模板的模型
+    Generate,
+}
+
+/// 本地 Ollama 服务的配置，提示词模板与 OpenAI 兼容接口相同
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct OllamaConfig {
+    pub base_url: String,
+    pub model: String,
+    pub api: OllamaApi,
+    pub temperature: f32,
+    pub system_prompt: String,
+    pub user_prompt: String,
+    pub glossary: String,
+}
+
+impl Default for OllamaConfig {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            model: String::new(),
+            api: OllamaApi::default(),
+            temperature: 0.2,
+            system_prompt: DEFAULT_SYSTEM_PROMPT.to_string(),
+            user_prompt: DEFAULT_USER_PROMPT.to_string(),
+            glossary: String::new(),
+        }
+    }
+}
+
+/// `/api/tags` 中的已安装模型
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OllamaModel {
+    pub name: String,
+    #[serde(default)]
+    pub size: u64,
+}
+
+#[derive(Serialize)]
+struct Options {
+    temperature: f32,
+}
+
+#[derive(Serialize)]
+struct ChatMessage<'a> {
+    role: &'static str,
+    content: &'a str,
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<ChatMessage<'a>>,
+    stream: bool,
+    options: Options,
+}
+
+#[derive(Serialize)]
+struct GenerateRequest<'a> {
+    model: &'a str,
+    prompt: &'a str,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    system: &'a str,
+    stream: bool,
+    options: Options,
+}
+
+/// `/api/chat` 与 `/api/generate` 的响应（流式时每行一个）
+#[derive(Deserialize)]
+struct OllamaResponse {
+    /// `/api/chat` 返回的消息
+    message: Option<ResponseMessage>,
+    /// `/api/generate` 返回的文本
+    response: Option<String>,
+    error: Option<String>,
+    #[serde(default)]
+    done: bool,
+}
+
+impl OllamaResponse {
+    fn content(self) -> Result<String, ProviderError> {
+        if let Some(error) = self.error {
+            return Err(ProviderError::Config(format!("Ollama: {}", error)));
+        }
+        Ok(self
+            .message
+            .map(|m| m.content)
+            .or(self.response)
+            .unwrap_or_default())
+    }
+}
+
+#[derive(Deserialize)]
+struct ResponseMessage {
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct TagsResponse {
+    models: Vec<OllamaModel>,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+pub struct Ollama {
+    config: OllamaConfig,
+    client: reqwest::Client,
+}
+
+impl Ollama {
+    pub fn new(config: OllamaConfig) -> Result<Self, ProviderError> {
+        if !config.base_url.starts_with("http://") && !config.base_url.starts_with("https://") {
+            return Err(ProviderError::Config(format!(
+                "Ollama 服务地址无效: {}",
+                config.base_url
+            )));
+        }
+        Ok(Self {
+            config,
+            client: http_client(ID)?,
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.config.base_url.trim_end_matches('/'), path)
+    }
+
+    fn model(&self) -> Result<&str, ProviderError> {
+        let model = self.config.model.trim();
+        if model.is_empty() {
+            return Err(ProviderError::Config("未选择 Ollama 模型".to_string()));
+        }
+        Ok(model)
+    }
+
+    /// 列出本机已安装的模型
+    pub async fn list_models(&self) -> Result<Vec<OllamaModel>, ProviderError> {
+        let url = self.url("/api/tags");
+        info!("请求 API: {}", url);
+
+        let resp = self.client.get(&url).send().await?;
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(ProviderError::Status(status.as_u16()));
+        }
+
+        let body = resp.text().await.unwrap_or_default();
+        let data = serde_json::from_str::<TagsResponse>(&body)
+            .map_err(|e| ProviderError::Parse(e.to_string()))?;
+        Ok(data.models)
+    }
+
+    async fn send(
+        &self,
+        req: &TranslateRequest,
+        stream: bool,
+    ) -> Result<reqwest::Response, ProviderError> {
+        let model = self.model()?;
+        let system_prompt = prompt::render(&self.config.system_prompt, req, &self.config.glossary);
+        let user_prompt = prompt::render(&self.config.user_prompt, req, &self.config.glossary);
+        debug!("System prompt: {}", system_prompt);
+
+        let options = Options {
+            temperature: self.config.temperature,
+        };
+        let builder = match self.config.api {
+            OllamaApi::Chat => {
+                let mut messages = Vec::with_capacity(2);
+                if !system_prompt.trim().is_empty() {
+                    messages.push(ChatMessage {
+                        role: "system",
+                        content: &system_prompt,
+                    });
+                }
+                messages.push(ChatMessage {
+                    role: "user",
+                    content: &user_prompt,
+                });
+                self.client.post(self.url("/api/chat")).json(&ChatRequest {
+                    model,
+                    messages,
+                    stream,
+                    options,
+                })
+            }
+            OllamaApi::Generate => {
+                self.client
+                    .post(self.url("/api/generate"))
+                    .json(&GenerateRequest {
+                        model,
+                        prompt: &user_prompt,
+                        system: system_prompt.trim(),
+                        stream,
+                        options,
+                    })
+            }
+        };
+        info!(
+            "请求 Ollama: {:?} (model: {}, stream: {})",
+            self.config.api, model, stream
+        );
+
+        let resp = builder.send().await.map_err(|e| {
+            error!("API 请求失败: {}", e);
+            ProviderError::from(e)
+        })?;
+
+        let status = resp.status();
+        info!("API 响应状态码: {}", status);
+
+        if !status.is_success() {
+            let retry_after = retry_after(resp.headers());
+            let body = resp.text().await.unwrap_or_default();
+            warn!("Ollama 返回非 2xx 状态码: {}, 内容: {}", status, body);
+            return Err(match serde_json::from_str::<ErrorResponse>(&body) {
+                Ok(e) => ProviderError::Config(format!("Ollama: {}", e.error)),
+                Err(_) => ProviderError::Status(status.as_u16()),
+            }
+            .with_retry_after(retry_after));
+        }
+
+        Ok(resp)
+    }
+}
+
+#[async_trait]
+impl TranslationProvider for Ollama {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            auto_detect: true,
+            streaming: true,
+            ..Capabilities::default()
+        }
+    }
+
+    async fn supported_languages(&self) -> Result<Vec<Language>, ProviderError> {
+        Ok(default_languages())
+    }
+
+    async fn translate(&self, req: &TranslateRequest) -> Result<Translation, ProviderError> {
+        let resp = self.send(req, false).await?;
+        let body = resp.text().await.unwrap_or_default();
+        debug!("API 响应内容: {}", body);
+
+        let data = serde_json::from_str::<OllamaResponse>(&body).map_err(|e| {
+            error!("解析 API 响应失败: {}", e);
+            error!("原始响应: {}", body);
+            ProviderError::Parse(e.to_string())
+        })?;
+
+        Ok(Translation {
+            text: data.content()?.trim().to_string(),
+            detected_source_lang: None,
+        })
+    }
+
+    async fn translate_stream(
+        &self,
+        req: &TranslateRequest,
+        on_chunk: ChunkSink<'_>,
+    ) -> Result<Translation, ProviderError> {
+        // Ollama 的流式响应是 NDJSON，每行一个 JSON 对象
+        let resp = self.send(req, true).await?;
+        let mut lines = LineReader::new(resp);
+        let mut text = String::new();
+
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let chunk = serde_json::from_str::<OllamaResponse>(&line).map_err(|e| {
+                error!("解析流式响应失败: {}, 原始数据: {}", e, line);
+                ProviderError::Parse(e.to_string())
+            })?;
+            let done = chunk.done;
+
+            let delta = chunk.content()?;
+            let delta = if text.is_empty() {
+                delta.trim_start()
+            } else {
+                delta.as_str()
+            };
+            if !delta.is_empty() {
+                on_chunk(delta);
+                text.push_str(delta);
+            }
+
+            if done {
+                break;
+            }
+        }
+
+        Ok(Translation {
+            text: text.trim_end().to_string(),
+            detected_source_lang: None,
+        })
+    }
+
+    /// 检查 Ollama 是否可以连接，以及所选模型是否已安装
+    async fn validate(&self) -> Result<(), ProviderError> {
+        let model = self.model()?;
+        let models = self.list_models().await?;
+
+        // 未写标签的模型名等同于 `:latest`
+        let installed = models
+            .iter()
+            .any(|m| m.name == model || m.name.strip_suffix(":latest") == Some(model));
+        if !installed {
+            return Err(ProviderError::Config(format!(
+                "Ollama 中未安装模型 {}，请先执行 ollama pull {}",
+                model, model
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,404 @@
+use async_trait::async_trait;
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+
+use super::prompt::{self, DEFAULT_SYSTEM_PROMPT, DEFAULT_USER_PROMPT};
+use super::retry::retry_after;
+use super::stream::{sse_data, LineReader};
+use super::{
+    default_languages, http_client, Capabilities, ChunkSink, Language, ProviderError,
+    TranslateRequest, Translation, TranslationProvider,
+};
+
+pub const ID: &str = "openai";
+
+const DEFAULT_BASE_URL: &str = "http://localhost:8000/v1";
+
+/// OpenAI 兼容接口（vLLM、LM Studio、llama.cpp server 等）的配置
+///
+/// 提示词模板支持 `{source}`、`{target}`、`{text}`、`{glossary}` 占位符
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct OpenAiConfig {
+    /// 该服务专用的 API Key，为空时使用通用的 `api_key`
+    pub api_key: String,
+    pub base_url: String,
+    pub model: String,
+    pub temperature: f32,
+    pub system_prompt: String,
+    pub user_prompt: String,
+    /// 术语表，每行一条 `原文=译文`
+    pub glossary: String,
+}
+
+impl Default for OpenAiConfig {
+    fn default() -> Self {
+        Self {
+            api_key: String::new(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            model: String::new(),
+            temperature: 0.2,
+            system_prompt: DEFAULT_SYSTEM_PROMPT.to_string(),
+            user_prompt: DEFAULT_USER_PROMPT.to_string(),
+            glossary: String::new(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ChatMessage<'a> {
+    role: &'static str,
+    content: &'a str,
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<ChatMessage<'a>>,
+    temperature: f32,
+    stream: bool,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatResponseMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatResponseMessage {
+    content: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ChatStreamChunk {
+    choices: Vec<ChatStreamChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChatStreamChoice {
+    delta: ChatResponseMessage,
+}
+
+pub struct OpenAi {
+    api_key: String,
+    config: OpenAiConfig,
+    client: reqwest::Client,
+}
+
+impl OpenAi {
+    pub fn new(api_key: String, config: OpenAiConfig) -> Result<Self, ProviderError> {
+        if config.model.trim().is_empty() {
+            return Err(ProviderError::Config("未配置模型名称".to_string()));
+        }
+        if !config.base_url.starts_with("http://") && !config.base_url.starts_with("https://") {
+            return Err(ProviderError::Config(format!(
+                "OpenAI 兼容服务地址无效: {}",
+                config.base_url
+            )));
+        }
+        Ok(Self {
+            api_key,
+            config,
+            client: http_client(ID)?,
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.config.base_url.trim_end_matches('/'), path)
+    }
+
+    /// 本地服务通常不需要 Key，只有填写了才带上 Authorization 头
+    fn authorize(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        if self.api_key.trim().is_empty() {
+            builder
+        } else {
+            builder.bearer_auth(self.api_key.trim())
+        }
+    }
+
+    async fn send(
+        &self,
+        req: &TranslateRequest,
+        stream: bool,
+    ) -> Result<reqwest::Response, ProviderError> {
+        let url = self.url("/chat/completions");
+        info!(
+            "请求 API: {} (model: {}, stream: {})",
+            url, self.config.model, stream
+        );
+
+        let system_prompt = prompt::render(&self.config.system_prompt, req, &self.config.glossary);
+        let user_prompt = prompt::render(&self.config.user_prompt, req, &self.config.glossary);
+        debug!("System prompt: {}", system_prompt);
+
+        let mut messages = Vec::with_capacity(2);
+        if !system_prompt.trim().is_empty() {
+            messages.push(ChatMessage {
+                role: "system",
+                content: &system_prompt,
+            });
+        }
+        messages.push(ChatMessage {
+            role: "user",
+            content: &user_prompt,
+        });
+
+        let body = ChatRequest {
+            model: &self.config.model,
+            messages,
+            temperature: self.config.temperature,
+            stream,
+        };
+
+        let resp = self
+            .authorize(self.client.post(&url))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| {
+                error!("API 请求失败: {}", e);
+                ProviderError::from(e)
+            })?;
+
+        let status = resp.status();
+        info!("API 响应状态码: {}", status);
+
+        if !status.is_success() {
+            let retry_after = retry_after(resp.headers());
+            let body = resp.text().await.unwrap_or_default();
+            warn!("API 返回非 2xx 状态码: {}, 内容: {}", status, body);
+            return Err(ProviderError::Status(status.as_u16()).with_retry_after(retry_after));
+        }
+
+        Ok(resp)
+    }
+}
+
+#[async_trait]
+impl TranslationProvider for OpenAi {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            auto_detect: true,
+            streaming: true,
+            ..Capabilities::default()
+        }
+    }
+
+    async fn supported_languages(&self) -> Result<Vec<Language>, ProviderError> {
+        Ok(default_languages())
+    }
+
+    async fn translate(&self, req: &TranslateRequest) -> Result<Translation, ProviderError> {
+        let resp = self.send(req, false).await?;
+        let body = resp.text().await.unwrap_or_default();
+        debug!("API 响应内容: {}", body);
+
+        let data = serde_json::from_str::<ChatResponse>(&body).map_err(|e| {
+            error!("解析 API 响应失败: {}", e);
+            error!("原始响应: {}", body);
+            ProviderError::Parse(e.to_string())
+        })?;
+
+        let text = data
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|c| c.message.content)
+            .ok_or_else(|| ProviderError::Parse("响应中没有 choices".to_string()))?;
+
+        Ok(Translation {
+            text: text.trim().to_string(),
+            detected_source_lang: None,
+        })
+    }
+
+    async fn translate_stream(
+        &self,
+        req: &TranslateRequest,
+        on_chunk: ChunkSink<'_>,
+    ) -> Result<Translation, ProviderError> {
+        let resp = self.send(req, true).await?;
+        let mut lines = LineReader::new(resp);
+        let mut text = String::new();
+
+        while let Some(line) = lines.next_line().await? {
+            let Some(data) = sse_data(&line) else {
+                continue;
+            };
+            if data == "[DONE]" {
+                break;
+            }
+
+            let chunk = serde_json::from_str::<ChatStreamChunk>(data).map_err(|e| {
+                error!("解析流式响应失败: {}, 原始数据: {}", e, data);
+                ProviderError::Parse(e.to_string())
+            })?;
+
+            for choice in chunk.choices {
+                let Some(delta) = choice.delta.content else {
+                    continue;
+                };
+                // 模型输出开头常带空白，与非流式结果保持一致
+                let delta = if text.is_empty() {
+                    delta.trim_start()
+                } else {
+                    delta.as_str()
+                };
+                if !delta.is_empty() {
+                    on_chunk(delta);
+                    text.push_str(delta);
+                }
+            }
+        }
+
+        Ok(Translation {
+            text: text.trim_end().to_string(),
+            detected_source_lang: None,
+        })
+    }
+
+    async fn validate(&self) -> Result<(), ProviderError> {
+        let resp = self
+            .authorize(self.client.get(self.url("/models")))
+            .send()
+            .await?;
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(ProviderError::Status(status.as_u16()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// 只应答一次的本地 HTTP 服务，响应体分成 `parts` 逐段写出以模拟网络分片；
+    /// 返回服务地址与收到的请求
+    async fn stub_server(
+        content_type: &'static str,
+        parts: &'static [&'static str],
+    ) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/v1", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            loop {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((headers, body)) = text.split_once("\r\n\r\n") {
+                    let length = headers
+                        .lines()
+                        .find_map(|line| {
+                            line.to_lowercase()
+                                .strip_prefix("content-length:")
+                                .map(|v| v.trim().to_string())
+                        })
+                        .and_then(|v| v.parse::<usize>().ok())
+                        .unwrap_or(0);
+                    if body.len() >= length {
+                        break;
+                    }
+                }
+                if n == 0 {
+                    break;
+                }
+            }
+
+            let length: usize = parts.iter().map(|p| p.len()).sum();
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                content_type, length
+            );
+            socket.write_all(head.as_bytes()).await.unwrap();
+            for part in parts {
+                // 客户端读到 [DONE] 后可能提前断开
+                if socket.write_all(part.as_bytes()).await.is_err() {
+                    break;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            }
+            String::from_utf8_lossy(&request).to_string()
+        });
+        (base_url, handle)
+    }
+
+    fn provider(base_url: String) -> OpenAi {
+        OpenAi::new(
+            String::new(),
+            OpenAiConfig {
+                base_url,
+                model: "stub-model".to_string(),
+                ..OpenAiConfig::default()
+            },
+        )
+        .unwrap()
+    }
+
+    fn request() -> TranslateRequest {
+        TranslateRequest {
+            text: "Hello, world".to_string(),
+            source_lang: "EN".to_string(),
+            target_lang: "ZH".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn translate_reads_first_choice() {
+        let (base_url, server) = stub_server(
+            "application/json",
+            &[r#"{"choices":[{"message":{"role":"assistant","content":"  你好，世界\n"}}]}"#],
+        )
+        .await;
+
+        let translation = provider(base_url).translate(&request()).await.unwrap();
+        assert_eq!(translation.text, "你好，世界");
+
+        let sent = server.await.unwrap();
+        assert!(sent.starts_with("POST /v1/chat/completions "));
+        assert!(sent.contains(r#""model":"stub-model""#));
+        assert!(sent.contains(r#""stream":false"#));
+    }
+
+    #[tokio::test]
+    async fn translate_stream_parses_sse_until_done() {
+        let (base_url, server) = stub_server(
+            "text/event-stream",
+            &[
+                "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n",
+                "data: {\"choices\":[{\"delta\":{\"content\":\" 你好\"}}]}\n\ndata: {\"choi",
+                "ces\":[{\"delta\":{\"content\":\"，世界\"}}]}\r\n\r\n: keep-alive\n\n",
+                "data: [DONE]\n\ndata: {\"choices\":[{\"delta\":{\"content\":\"ignored\"}}]}\n\n",
+            ],
+        )
+        .await;
+
+        let chunks = Mutex::new(Vec::new());
+        let sink = |delta: &str| chunks.lock().unwrap().push(delta.to_string());
+        let translation = provider(base_url)
+            .translate_stream(&request(), &sink)
+            .await
+            .unwrap();
+        assert_eq!(translation.text, "你好，世界");
+        assert_eq!(chunks.into_inner().unwrap(), ["你好", "，世界"]);
+
+        let sent = server.await.unwrap();
+        assert!(sent.contains(r#""stream":true"#));
+    }
+}
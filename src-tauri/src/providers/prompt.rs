@@ -0,0 +1,60 @@
+use super::template;
+use super::{default_languages, is_auto_detect, TranslateRequest};
+
+pub(crate) const DEFAULT_SYSTEM_PROMPT: &str = "You are a professional translation engine. \
+Translate the user's text from {source} to {target}. \
+Only output the translation, without explanations or quotes.\n{glossary}";
+pub(crate) const DEFAULT_USER_PROMPT: &str = "{text}";
+
+/// 填充 LLM 提示词模板中的 `{source}`、`{target}`、`{text}`、`{glossary}` 占位符
+pub(crate) fn render(template: &str, req: &TranslateRequest, glossary: &str) -> String {
+    let glossary = if glossary.trim().is_empty() {
+        String::new()
+    } else {
+        format!("Use the following glossary:\n{}", glossary.trim())
+    };
+    let source = language_name(&req.source_lang);
+    let target = language_name(&req.target_lang);
+    let values = [
+        ("source", source.as_str()),
+        ("target", target.as_str()),
+        ("glossary", glossary.as_str()),
+        ("text", req.text.as_str()),
+    ];
+    template::render(template, &values, str::to_string)
+}
+
+/// 提示词中使用语言名称，模型对 "简体中文" 比 "ZH" 理解得更准确
+fn language_name(code: &str) -> String {
+    if is_auto_detect(code) {
+        return "the detected language".to_string();
+    }
+    default_languages()
+        .into_iter()
+        .find(|l| l.code.eq_ignore_ascii_case(code))
+        .map(|l| format!("{} ({})", l.name, l.code))
+        .unwrap_or_else(|| code.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_not_re_expanded() {
+        let req = TranslateRequest {
+            text: "keep {target} and {glossary}".to_string(),
+            source_lang: "EN".to_string(),
+            target_lang: "ZH".to_string(),
+        };
+        assert_eq!(
+            render(
+                "{glossary}\n{source} -> {target}: {text}",
+                &req,
+                "{text}={source}"
+            ),
+            "Use the following glossary:\n{text}={source}\n\
+             English (EN) -> 简体中文 (ZH): keep {target} and {glossary}"
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use super::ProviderError;
+
+/// 同一服务内的重试策略，只对 `ProviderError::should_retry_in_place` 的错误生效
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct RetryConfig {
+    /// 包含第一次请求在内的最多尝试次数，1 表示不重试
+    pub max_attempts: u32,
+    /// 第一次重试前的等待，之后每次翻倍
+    pub base_delay_ms: u64,
+    /// 单次等待的上限，服务通过 Retry-After 要求的等待同样受此限制
+    pub max_delay_ms: u64,
+    /// 在等待时间上加入随机抖动，避免多个请求同时重试
+    pub jitter: bool,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay_ms: 500,
+            max_delay_ms: 10_000,
+            jitter: true,
+        }
+    }
+}
+
+/// 一次带重试的请求的结果
+pub struct RetryOutcome<T> {
+    pub result: Result<T, ProviderError>,
+    /// 实际发出的请求次数
+    pub attempts: u32,
+    /// 最后一次失败的原因，重试后成功时同样保留
+    pub last_error: Option<ProviderError>,
+}
+
+/// 解析 Retry-After 响应头，支持秒数与 HTTP 日期（`Sun, 06 Nov 1994 08:49:37 GMT`）两种格式
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    // 日期已经过去时不再等待
+    Some(
+        at.signed_duration_since(Utc::now())
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+/// 第 `attempt` 次失败后的等待时间：优先使用服务要求的时间，否则按指数退避
+fn backoff(config: &RetryConfig, attempt: u32, error: &ProviderError) -> Duration {
+    let max = Duration::from_millis(config.max_delay_ms);
+    if let Some(after) = error.retry_after() {
+        return after.min(max);
+    }
+
+    let exp = config
+        .base_delay_ms
+        .saturating_mul(1u64 << (attempt - 1).min(16));
+    let mut delay = Duration::from_millis(exp).min(max);
+    if config.jitter {
+        // 在 [delay/2, delay] 内随机取值
+        let half = delay.as_millis() as u64 / 2;
+        let random = RandomState::new().build_hasher().finish();
+        delay = Duration::from_millis(half + random % (half + 1));
+    }
+    delay
+}
+
+/// 按策略执行 `op`，遇到可重试的错误时等待后重新请求
+///
+/// `op` 返回的第二个值为 false 时表示本次请求已经产生了输出（如流式分片），
+/// 此时不再重试，避免重复输出
+pub async fn run<T, F, Fut>(config: &RetryConfig, id: &str, mut op: F) -> RetryOutcome<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = (Result<T, ProviderError>, bool)>,
+{
+    let max_attempts = config.max_attempts.max(1);
+    let mut last_error = None;
+    let mut attempt = 1;
+
+    loop {
+        let (result, can_retry) = op().await;
+        let error = match result {
+            Ok(value) => {
+                if attempt > 1 {
+                    info!("{} 第 {} 次尝试成功", id, attempt);
+                }
+                return RetryOutcome {
+                    result: Ok(value),
+                    attempts: attempt,
+                    last_error,
+                };
+            }
+            Err(e) => e,
+        };
+
+        if !error.should_retry_in_place() || !can_retry || attempt >= max_attempts {
+            if attempt > 1 {
+                warn!("{} 尝试 {} 次后仍失败: {}", id, attempt, error);
+            }
+            return RetryOutcome {
+                result: Err(error.clone()),
+                attempts: attempt,
+                last_error: Some(error),
+            };
+        }
+
+        let delay = backoff(config, attempt, &error);
+        warn!(
+            "{} 第 {} 次尝试失败: {}，{} ms 后重试",
+            id,
+            attempt,
+            error,
+            delay.as_millis()
+        );
+        last_error = Some(error);
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn config() -> RetryConfig {
+        RetryConfig {
+            base_delay_ms: 1,
+            max_delay_ms: 1,
+            ..RetryConfig::default()
+        }
+    }
+
+    async fn attempts_for(error: ProviderError) -> u32 {
+        let calls = Cell::new(0);
+        let outcome = run(&config(), "test", || {
+            calls.set(calls.get() + 1);
+            let error = error.clone();
+            async move { (Err::<(), _>(error), true) }
+        })
+        .await;
+        assert_eq!(outcome.attempts, calls.get());
+        outcome.attempts
+    }
+
+    #[tokio::test]
+    async fn transient_errors_are_retried_in_place() {
+        assert_eq!(attempts_for(ProviderError::Status(503)).await, 3);
+        assert_eq!(
+            attempts_for(ProviderError::Network("reset".into())).await,
+            3
+        );
+        let limited =
+            ProviderError::Quota("too many requests".into()).with_retry_after(Some(Duration::ZERO));
+        assert_eq!(attempts_for(limited).await, 3);
+    }
+
+    #[tokio::test]
+    async fn exhausted_quota_and_local_rejections_fail_fast() {
+        assert_eq!(attempts_for(ProviderError::Quota("quota".into())).await, 1);
+        assert_eq!(attempts_for(ProviderError::Api(456)).await, 1);
+        assert_eq!(attempts_for(ProviderError::Status(400)).await, 1);
+        let open = ProviderError::CircuitOpen("test".into(), Duration::from_secs(1));
+        assert_eq!(attempts_for(open).await, 1);
+    }
+
+    #[test]
+    fn retry_after_accepts_seconds_and_http_dates() {
+        let header = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(RETRY_AFTER, value.parse().unwrap());
+            retry_after(&headers)
+        };
+        assert_eq!(header("120"), Some(Duration::from_secs(120)));
+        assert_eq!(
+            header("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(Duration::ZERO)
+        );
+        let later = (Utc::now() + chrono::Duration::seconds(90)).to_rfc2822();
+        let wait = header(&later.replace("+0000", "GMT")).unwrap();
+        assert!(wait > Duration::from_secs(80) && wait <= Duration::from_secs(90));
+        assert_eq!(header("soon"), None);
+    }
+
+    #[tokio::test]
+    async fn streamed_output_stops_retrying() {
+        let outcome = run(&config(), "test", || async {
+            (Err::<(), _>(ProviderError::Status(503)), false)
+        })
+        .await;
+        assert_eq!(outcome.attempts, 1);
+    }
+}
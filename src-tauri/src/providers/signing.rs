@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+pub(crate) fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+pub(crate) fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC 接受任意长度的密钥");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// 签名用到的 UTC 时间
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct SigningTime {
+    pub timestamp: u64,
+    time: DateTime<Utc>,
+}
+
+impl SigningTime {
+    pub fn now() -> Self {
+        Self::from_timestamp(Utc::now().timestamp().max(0) as u64)
+    }
+
+    pub fn from_timestamp(timestamp: u64) -> Self {
+        Self {
+            timestamp,
+            time: DateTime::from_timestamp(timestamp as i64, 0).unwrap_or_default(),
+        }
+    }
+
+    /// `2024-01-31`，腾讯云凭据范围中的日期
+    pub fn date_dashed(&self) -> String {
+        self.time.format("%Y-%m-%d").to_string()
+    }
+
+    /// `20240131`，AWS 凭据范围中的日期
+    pub fn date_compact(&self) -> String {
+        self.time.format("%Y%m%d").to_string()
+    }
+
+    /// `20240131T080000Z`，AWS 的 `X-Amz-Date`
+    pub fn amz_date(&self) -> String {
+        self.time.format("%Y%m%dT%H%M%SZ").to_string()
+    }
+}
@@ -0,0 +1,50 @@
+use super::ProviderError;
+
+/// 按行读取 HTTP 响应体，供 SSE (`text/event-stream`) 与 NDJSON 接口共用
+///
+/// 网络分片可能在任意字节处断开，未完整的行会留在缓冲区中
+pub(crate) struct LineReader {
+    resp: reqwest::Response,
+    buf: Vec<u8>,
+    finished: bool,
+}
+
+impl LineReader {
+    pub fn new(resp: reqwest::Response) -> Self {
+        Self {
+            resp,
+            buf: Vec::new(),
+            finished: false,
+        }
+    }
+
+    /// 返回下一行（不含换行符），响应结束时返回 `None`
+    pub async fn next_line(&mut self) -> Result<Option<String>, ProviderError> {
+        loop {
+            if let Some(pos) = self.buf.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = self.buf.drain(..=pos).collect();
+                let line = String::from_utf8_lossy(&line);
+                return Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()));
+            }
+
+            if self.finished {
+                if self.buf.is_empty() {
+                    return Ok(None);
+                }
+                let line = String::from_utf8_lossy(&self.buf).to_string();
+                self.buf.clear();
+                return Ok(Some(line));
+            }
+
+            match self.resp.chunk().await? {
+                Some(chunk) => self.buf.extend_from_slice(&chunk),
+                None => self.finished = true,
+            }
+        }
+    }
+}
+
+/// SSE 事件的数据行，`data: [DONE]` 等结束标记由调用方判断
+pub(crate) fn sse_data(line: &str) -> Option<&str> {
+    line.strip_prefix("data:").map(|data| data.trim_start())
+}
@@ -0,0 +1,57 @@
+/// 替换模板中的 `{name}` 占位符，`escape` 负责把值转换为所在位置需要的格式
+///
+/// 只扫描一遍模板，代入的文本中即使含有 `{api_key}`、`{text}` 之类的字样也不会再被替换；
+/// 不认识的占位符原样保留
+pub(crate) fn render(
+    template: &str,
+    values: &[(&str, &str)],
+    escape: impl Fn(&str) -> String,
+) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start + 1..];
+        let value = tail.find('}').and_then(|end| {
+            values
+                .iter()
+                .find(|(name, _)| *name == &tail[..end])
+                .map(|(_, value)| (end, value))
+        });
+        match value {
+            Some((end, value)) => {
+                out.push_str(&escape(value));
+                rest = &tail[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = tail;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_not_re_expanded() {
+        let values = [
+            ("text", "{api_key} {target}"),
+            ("source", "EN"),
+            ("target", "ZH"),
+            ("api_key", "secret"),
+        ];
+        assert_eq!(
+            render(
+                "{\"q\":\"{text}\",\"to\":\"{target}\",\"k\":\"{api_key}\"}",
+                &values,
+                str::to_string
+            ),
+            "{\"q\":\"{api_key} {target}\",\"to\":\"ZH\",\"k\":\"secret\"}"
+        );
+    }
+}
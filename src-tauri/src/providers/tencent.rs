@@ -0,0 +1,322 @@
+use async_trait::async_trait;
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+
+use super::retry::retry_after;
+use super::signing::{hmac_sha256, sha256_hex, SigningTime};
+use super::{
+    default_languages, http_client, is_auto_detect, Capabilities, Language, ProviderError,
+    TranslateRequest, Translation, TranslationProvider,
+};
+
+pub const ID: &str = "tencent";
+
+const HOST: &str = "tmt.tencentcloudapi.com";
+const SERVICE: &str = "tmt";
+const API_VERSION: &str = "2018-03-21";
+const CONTENT_TYPE: &str = "application/json; charset=utf-8";
+
+/// 腾讯云 API 密钥，在访问管理控制台创建
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct TencentConfig {
+    pub secret_id: String,
+    pub secret_key: String,
+    pub region: String,
+    pub project_id: i64,
+}
+
+impl Default for TencentConfig {
+    fn default() -> Self {
+        Self {
+            secret_id: String::new(),
+            secret_key: String::new(),
+            region: "ap-guangzhou".to_string(),
+            project_id: 0,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct TextTranslateRequest<'a> {
+    source_text: &'a str,
+    source: &'a str,
+    target: &'a str,
+    project_id: i64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct LanguageDetectRequest<'a> {
+    text: &'a str,
+    project_id: i64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TencentResponse<T> {
+    response: TencentResult<T>,
+}
+
+/// 出错时 `Response` 中只有 `Error` 与 `RequestId`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TencentResult<T> {
+    Error {
+        #[serde(rename = "Error")]
+        error: TencentError,
+    },
+    Ok(T),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TencentError {
+    code: String,
+    message: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TextTranslateResponse {
+    target_text: String,
+    source: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct LanguageDetectResponse {
+    lang: String,
+}
+
+/// TC3-HMAC-SHA256 签名，返回 `Authorization` 头
+///
+/// 只签 content-type 与 host 两个头，与官方 SDK 的默认做法一致
+pub(crate) fn authorization(
+    secret_id: &str,
+    secret_key: &str,
+    time: &SigningTime,
+    payload: &str,
+) -> String {
+    let signed_headers = "content-type;host";
+    let canonical_request = format!(
+        "POST\n/\n\ncontent-type:{}\nhost:{}\n\n{}\n{}",
+        CONTENT_TYPE,
+        HOST,
+        signed_headers,
+        sha256_hex(payload.as_bytes())
+    );
+
+    let date = time.date_dashed();
+    let scope = format!("{}/{}/tc3_request", date, SERVICE);
+    let string_to_sign = format!(
+        "TC3-HMAC-SHA256\n{}\n{}\n{}",
+        time.timestamp,
+        scope,
+        sha256_hex(canonical_request.as_bytes())
+    );
+
+    let secret_date = hmac_sha256(format!("TC3{}", secret_key).as_bytes(), &date);
+    let secret_service = hmac_sha256(&secret_date, SERVICE);
+    let secret_signing = hmac_sha256(&secret_service, "tc3_request");
+    let signature = hex::encode(hmac_sha256(&secret_signing, &string_to_sign));
+
+    format!(
+        "TC3-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        secret_id, scope, signed_headers, signature
+    )
+}
+
+/// 腾讯云的错误码见 https://cloud.tencent.com/document/api/551/30637
+fn api_error(error: TencentError) -> ProviderError {
+    let code = error.code.as_str();
+    if code.starts_with("AuthFailure") {
+        return ProviderError::Config("腾讯云 SecretId 或 SecretKey 无效".to_string());
+    }
+    match code {
+        "RequestLimitExceeded" | "LimitExceeded" => {
+            ProviderError::Quota("腾讯翻译请求过于频繁，请稍后再试".to_string())
+        }
+        "FailedOperation.NoFreeAmount" | "FailedOperation.ServiceIsolate" => {
+            ProviderError::Quota("腾讯翻译本月免费额度已用完或服务已停用".to_string())
+        }
+        "InternalError" | "FailedOperation.RequestAiLabErr" => {
+            ProviderError::Network(format!("腾讯翻译: {}", error.message))
+        }
+        _ => ProviderError::Config(format!("腾讯翻译: {}", error.message)),
+    }
+}
+
+/// 腾讯翻译的语言代码是小写两位代码，中文为 `zh`
+fn to_tencent_lang(lang: &str) -> String {
+    lang.to_lowercase()
+}
+
+pub struct Tencent {
+    config: TencentConfig,
+    client: reqwest::Client,
+}
+
+impl Tencent {
+    pub fn new(config: TencentConfig) -> Result<Self, ProviderError> {
+        if config.secret_id.trim().is_empty() || config.secret_key.trim().is_empty() {
+            return Err(ProviderError::Config(
+                "腾讯云 SecretId 或 SecretKey 未配置，请在系统托盘菜单中打开设置".to_string(),
+            ));
+        }
+        Ok(Self {
+            config,
+            client: http_client(ID)?,
+        })
+    }
+
+    /// 调用一个 API，签名需要覆盖请求体，所以先序列化再发送
+    async fn call<Req: Serialize, Resp: for<'de> Deserialize<'de>>(
+        &self,
+        action: &str,
+        body: &Req,
+    ) -> Result<Resp, ProviderError> {
+        let payload =
+            serde_json::to_string(body).map_err(|e| ProviderError::Parse(e.to_string()))?;
+        let time = SigningTime::now();
+        let authorization = authorization(
+            self.config.secret_id.trim(),
+            self.config.secret_key.trim(),
+            &time,
+            &payload,
+        );
+
+        let url = format!("https://{}/", HOST);
+        info!("请求 API: {} ({})", url, action);
+        let resp = self
+            .client
+            .post(&url)
+            .header("Authorization", authorization)
+            .header("Content-Type", CONTENT_TYPE)
+            .header("X-TC-Action", action)
+            .header("X-TC-Timestamp", time.timestamp.to_string())
+            .header("X-TC-Version", API_VERSION)
+            .header("X-TC-Region", self.config.region.trim())
+            .body(payload)
+            .send()
+            .await
+            .map_err(|e| {
+                error!("API 请求失败: {}", e);
+                ProviderError::from(e)
+            })?;
+
+        let status = resp.status();
+        info!("API 响应状态码: {}", status);
+
+        let retry_after = retry_after(resp.headers());
+        let body = resp.text().await.unwrap_or_default();
+        debug!("API 响应内容: {}", body);
+
+        if !status.is_success() {
+            warn!("腾讯翻译返回非 2xx 状态码: {}", status);
+            return Err(ProviderError::Status(status.as_u16()).with_retry_after(retry_after));
+        }
+
+        let data = serde_json::from_str::<TencentResponse<Resp>>(&body).map_err(|e| {
+            error!("解析 API 响应失败: {}", e);
+            error!("原始响应: {}", body);
+            ProviderError::Parse(e.to_string())
+        })?;
+
+        match data.response {
+            TencentResult::Ok(resp) => Ok(resp),
+            TencentResult::Error { error } => {
+                warn!("腾讯翻译返回错误: {}, {}", error.code, error.message);
+                Err(api_error(error))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl TranslationProvider for Tencent {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            auto_detect: true,
+            ..Capabilities::default()
+        }
+    }
+
+    /// 腾讯翻译不支持荷兰语与波兰语
+    async fn supported_languages(&self) -> Result<Vec<Language>, ProviderError> {
+        Ok(default_languages()
+            .into_iter()
+            .filter(|l| l.code != "NL" && l.code != "PL")
+            .collect())
+    }
+
+    async fn translate(&self, req: &TranslateRequest) -> Result<Translation, ProviderError> {
+        let source = if is_auto_detect(&req.source_lang) {
+            "auto".to_string()
+        } else {
+            to_tencent_lang(&req.source_lang)
+        };
+        let target = to_tencent_lang(&req.target_lang);
+
+        let resp: TextTranslateResponse = self
+            .call(
+                "TextTranslate",
+                &TextTranslateRequest {
+                    source_text: &req.text,
+                    source: &source,
+                    target: &target,
+                    project_id: self.config.project_id,
+                },
+            )
+            .await?;
+
+        Ok(Translation {
+            text: resp.target_text,
+            detected_source_lang: resp
+                .source
+                .filter(|_| is_auto_detect(&req.source_lang))
+                .map(|l| l.to_uppercase()),
+        })
+    }
+
+    /// 语种识别只有几个字符，用来检查密钥与区域
+    async fn validate(&self) -> Result<(), ProviderError> {
+        let _: LanguageDetectResponse = self
+            .call(
+                "LanguageDetect",
+                &LanguageDetectRequest {
+                    text: "hello",
+                    project_id: self.config.project_id,
+                },
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn authorization_matches_known_vector() {
+        let payload = r#"{"SourceText":"hello","Source":"en","Target":"zh","ProjectId":0}"#;
+        let header = authorization(
+            "SID",
+            "SKEY",
+            &SigningTime::from_timestamp(1551113065),
+            payload,
+        );
+        assert_eq!(
+            header,
+            "TC3-HMAC-SHA256 Credential=SID/2019-02-25/tmt/tc3_request, \
+             SignedHeaders=content-type;host, \
+             Signature=33ccdc9fa17425240837160a5e6f63f552367f46be417bbdc8d6d2c84730518f"
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use async_trait::async_trait;
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::retry::retry_after;
+use super::{
+    default_languages, http_client, is_auto_detect, Capabilities, Language, ProviderError,
+    TranslateRequest, Translation, TranslationProvider,
+};
+
+pub const ID: &str = "youdao";
+
+const API_URL: &str = "https://openapi.youdao.com/api";
+
+/// 有道智云文本翻译的应用凭据
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct YoudaoConfig {
+    pub app_key: String,
+    pub app_secret: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YoudaoResponse {
+    error_code: String,
+    translation: Option<Vec<String>>,
+    /// 语言方向，如 `en2zh-CHS`
+    l: Option<String>,
+}
+
+/// 签名中使用的 input：超过 20 个字符时取前 10 个字符 + 长度 + 后 10 个字符
+pub(crate) fn truncate_input(query: &str) -> String {
+    let chars: Vec<char> = query.chars().collect();
+    if chars.len() <= 20 {
+        return query.to_string();
+    }
+    format!(
+        "{}{}{}",
+        chars[..10].iter().collect::<String>(),
+        chars.len(),
+        chars[chars.len() - 10..].iter().collect::<String>()
+    )
+}
+
+/// v3 签名：`SHA256(应用ID + input + salt + curtime + 应用密钥)` 的小写十六进制
+pub(crate) fn sign(
+    app_key: &str,
+    query: &str,
+    salt: &str,
+    curtime: &str,
+    app_secret: &str,
+) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(app_key.as_bytes());
+    hasher.update(truncate_input(query).as_bytes());
+    hasher.update(salt.as_bytes());
+    hasher.update(curtime.as_bytes());
+    hasher.update(app_secret.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+fn to_youdao_lang(lang: &str) -> String {
+    match lang {
+        "ZH" => "zh-CHS".to_string(),
+        other => other.to_lowercase(),
+    }
+}
+
+fn from_youdao_lang(lang: &str) -> String {
+    match lang {
+        "zh-CHS" => "ZH".to_string(),
+        other => other.to_uppercase(),
+    }
+}
+
+/// 有道的错误码见 https://ai.youdao.com/DOCSIRMA/html/trans/api/wbfy/index.html
+fn api_error(code: i64) -> ProviderError {
+    match code {
+        108 | 110 | 111 | 202 | 206 => {
+            ProviderError::Config("有道智云应用 ID 或密钥无效".to_string())
+        }
+        102 | 103 => ProviderError::Config("有道翻译不支持该语言方向或文本过长".to_string()),
+        401 => ProviderError::Quota("有道智云账户余额不足".to_string()),
+        411 | 412 => ProviderError::Quota("有道翻译请求过于频繁，请稍后再试".to_string()),
+        code => ProviderError::Api(code),
+    }
+}
+
+pub struct Youdao {
+    config: YoudaoConfig,
+    client: reqwest::Client,
+}
+
+impl Youdao {
+    pub fn new(config: YoudaoConfig) -> Result<Self, ProviderError> {
+        if config.app_key.trim().is_empty() || config.app_secret.trim().is_empty() {
+            return Err(ProviderError::Config(
+                "有道智云应用 ID 或密钥未配置，请在系统托盘菜单中打开设置".to_string(),
+            ));
+        }
+        Ok(Self {
+            config,
+            client: http_client(ID)?,
+        })
+    }
+}
+
+#[async_trait]
+impl TranslationProvider for Youdao {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            auto_detect: true,
+            ..Capabilities::default()
+        }
+    }
+
+    async fn supported_languages(&self) -> Result<Vec<Language>, ProviderError> {
+        Ok(default_languages())
+    }
+
+    async fn translate(&self, req: &TranslateRequest) -> Result<Translation, ProviderError> {
+        let app_key = self.config.app_key.trim();
+        let salt = uuid::Uuid::new_v4().to_string();
+        let curtime = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+            .to_string();
+        let from = if is_auto_detect(&req.source_lang) {
+            "auto".to_string()
+        } else {
+            to_youdao_lang(&req.source_lang)
+        };
+        let to = to_youdao_lang(&req.target_lang);
+        let sign = sign(
+            app_key,
+            &req.text,
+            &salt,
+            &curtime,
+            self.config.app_secret.trim(),
+        );
+
+        info!("请求 API: {}", API_URL);
+        let resp = self
+            .client
+            .post(API_URL)
+            .form(&[
+                ("q", req.text.as_str()),
+                ("from", from.as_str()),
+                ("to", to.as_str()),
+                ("appKey", app_key),
+                ("salt", salt.as_str()),
+                ("sign", sign.as_str()),
+                ("signType", "v3"),
+                ("curtime", curtime.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(|e| {
+                error!("API 请求失败: {}", e);
+                ProviderError::from(e)
+            })?;
+
+        let status = resp.status();
+        info!("API 响应状态码: {}", status);
+
+        let retry_after = retry_after(resp.headers());
+        let body = resp.text().await.unwrap_or_default();
+        debug!("API 响应内容: {}", body);
+
+        if !status.is_success() {
+            warn!("有道翻译返回非 2xx 状态码: {}", status);
+            return Err(ProviderError::Status(status.as_u16()).with_retry_after(retry_after));
+        }
+
+        let data = serde_json::from_str::<YoudaoResponse>(&body).map_err(|e| {
+            error!("解析 API 响应失败: {}", e);
+            error!("原始响应: {}", body);
+            ProviderError::Parse(e.to_string())
+        })?;
+
+        if data.error_code != "0" {
+            warn!("有道翻译返回错误码: {}", data.error_code);
+            let code = data
+                .error_code
+                .parse()
+                .map_err(|_| ProviderError::Parse(data.error_code.clone()))?;
+            return Err(api_error(code));
+        }
+
+        let text = data
+            .translation
+            .ok_or_else(|| ProviderError::Parse("响应中没有 translation".to_string()))?
+            .join("\n");
+
+        // 自动检测时从 `l` 中取出实际的源语言
+        let detected_source_lang = data
+            .l
+            .filter(|_| is_auto_detect(&req.source_lang))
+            .and_then(|l| l.split_once('2').map(|(from, _)| from_youdao_lang(from)));
+
+        Ok(Translation {
+            text,
+            detected_source_lang,
+        })
+    }
+
+    /// 有道没有免费的校验接口，翻译一个单词检查凭据
+    async fn validate(&self) -> Result<(), ProviderError> {
+        self.translate(&TranslateRequest {
+            text: "hello".to_string(),
+            source_lang: "EN".to_string(),
+            target_lang: "ZH".to_string(),
+        })
+        .await
+        .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LONG: &str = "这是一段超过二十个字符的中文文本，用来测试有道签名的截断规则。";
+
+    #[test]
+    fn short_input_is_signed_as_is() {
+        assert_eq!(truncate_input("hello"), "hello");
+        assert_eq!(
+            sign("appKey1", "hello", "salt-uuid", "1700000000", "secret"),
+            "8d8c20b6f9eb317b1d194ff35d871552ebc9c26846d03e1d722233e81140c5e4"
+        );
+    }
+
+    #[test]
+    fn long_input_is_truncated_before_signing() {
+        assert_eq!(
+            truncate_input(LONG),
+            "这是一段超过二十个字31有道签名的截断规则。"
+        );
+        assert_eq!(
+            sign("appKey1", LONG, "salt-uuid", "1700000000", "secret"),
+            "affc5b4da6bc253705e8602541908b486222c61d530d1b555d976338e03125fc"
+        );
+    }
+}
@@ -0,0 +1,336 @@
+use chrono::Local;
+use log::{info, warn};
+use rusqlite::{params, params_from_iter, OptionalExtension, Row};
+use serde::Serialize;
+use std::path::Path;
+
+use crate::db::{now, Database};
+
+/// 答错的卡片在这之后重新出现，当天反复练习直到答对
+const RELEARN_DELAY_SECS: i64 = 10 * 60;
+
+/// 生词本中的一张卡片及其复习状态
+#[derive(Serialize, Clone, Debug)]
+pub struct VocabularyCard {
+    pub id: i64,
+    pub word: String,
+    pub translation: String,
+    pub source_lang: String,
+    pub target_lang: String,
+    /// 音标，翻译服务不提供时为空
+    pub phonetic: Option<String>,
+    pub example: Option<String>,
+    pub created_at: i64,
+    /// 连续答对的次数，答错后归零
+    pub repetitions: u32,
+    /// 当前的复习间隔（天）
+    pub interval_days: u32,
+    /// SM-2 的难度系数，越小复习越频繁，最低 1.3
+    pub ease_factor: f64,
+    /// 答错的总次数
+    pub lapses: u32,
+    /// 下次复习的时间（Unix 时间戳，秒）
+    pub due_at: i64,
+    pub reviewed_at: Option<i64>,
+}
+
+/// 加入生词本的单词，已存在时更新译文
+pub struct NewCard<'a> {
+    pub word: &'a str,
+    pub translation: &'a str,
+    pub source_lang: &'a str,
+    pub target_lang: &'a str,
+    pub phonetic: Option<&'a str>,
+    pub example: Option<&'a str>,
+}
+
+static DB: Database = Database::new(
+    "Vocabulary notebook is not available",
+    "Vocabulary database error",
+);
+
+/// 本地时间下一个零点，在此之前到期的卡片都算作今日待复习
+fn end_of_today() -> i64 {
+    Local::now()
+        .date_naive()
+        .succ_opt()
+        .and_then(|day| day.and_hms_opt(0, 0, 0))
+        .and_then(|midnight| midnight.and_local_timezone(Local).earliest())
+        .map(|midnight| midnight.timestamp())
+        .unwrap_or_else(|| now() + 86_400)
+}
+
+/// 选中的文本是否为单词或短语，只有这些文本可以加入生词本
+pub fn is_candidate(text: &str) -> bool {
+    let text = text.trim();
+    !text.is_empty()
+        && !text.contains('\n')
+        && text.chars().count() <= 40
+        && text.split_whitespace().count() <= 4
+        && text.chars().any(char::is_alphabetic)
+}
+
+/// 打开（或创建）生词本数据库，失败时生词本不可用但不影响翻译
+pub fn init(path: &Path) {
+    let opened = DB.open(
+        path,
+        "CREATE TABLE IF NOT EXISTS cards (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            word TEXT NOT NULL COLLATE NOCASE,
+            translation TEXT NOT NULL,
+            source_lang TEXT NOT NULL,
+            target_lang TEXT NOT NULL,
+            phonetic TEXT,
+            example TEXT,
+            created_at INTEGER NOT NULL,
+            repetitions INTEGER NOT NULL DEFAULT 0,
+            interval_days INTEGER NOT NULL DEFAULT 0,
+            ease_factor REAL NOT NULL DEFAULT 2.5,
+            lapses INTEGER NOT NULL DEFAULT 0,
+            due_at INTEGER NOT NULL,
+            reviewed_at INTEGER,
+            UNIQUE (word, source_lang, target_lang)
+        );
+        CREATE INDEX IF NOT EXISTS cards_due_at ON cards (due_at);",
+    );
+    match opened {
+        Ok(()) => info!("生词本: {}", path.display()),
+        Err(e) => warn!("打开生词本失败，本次运行不能使用生词本: {}", e),
+    }
+}
+
+fn read_card(row: &Row<'_>) -> rusqlite::Result<VocabularyCard> {
+    Ok(VocabularyCard {
+        id: row.get("id")?,
+        word: row.get("word")?,
+        translation: row.get("translation")?,
+        source_lang: row.get("source_lang")?,
+        target_lang: row.get("target_lang")?,
+        phonetic: row.get("phonetic")?,
+        example: row.get("example")?,
+        created_at: row.get("created_at")?,
+        repetitions: row.get("repetitions")?,
+        interval_days: row.get("interval_days")?,
+        ease_factor: row.get("ease_factor")?,
+        lapses: row.get("lapses")?,
+        due_at: row.get("due_at")?,
+        reviewed_at: row.get("reviewed_at")?,
+    })
+}
+
+/// 加入生词本，新卡片立即到期；已存在的单词（不区分大小写）只更新译文，保留复习进度
+pub fn add(card: &NewCard<'_>) -> Result<VocabularyCard, String> {
+    let word = card.word.trim();
+    if word.is_empty() {
+        return Err("Word must not be empty".to_string());
+    }
+    let now = now();
+    DB.with(|conn| {
+        conn.query_row(
+            "INSERT INTO cards
+             (word, translation, source_lang, target_lang, phonetic, example, created_at, due_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)
+             ON CONFLICT (word, source_lang, target_lang) DO UPDATE SET
+                translation = excluded.translation,
+                phonetic = COALESCE(excluded.phonetic, phonetic),
+                example = COALESCE(excluded.example, example)
+             RETURNING *",
+            params![
+                word,
+                card.translation.trim(),
+                card.source_lang,
+                card.target_lang,
+                card.phonetic,
+                card.example,
+                now
+            ],
+            read_card,
+        )
+    })
+}
+
+pub fn remove(id: i64) -> Result<(), String> {
+    let removed = DB.with(|conn| conn.execute("DELETE FROM cards WHERE id = ?1", params![id]))?;
+    if removed == 0 {
+        return Err(format!("Card {} not found", id));
+    }
+    Ok(())
+}
+
+/// 按 ID 取出卡片，不传 `ids` 时返回整个生词本，按加入时间排列
+pub fn cards(ids: Option<&[i64]>) -> Result<Vec<VocabularyCard>, String> {
+    let filter = match ids {
+        Some(ids) => format!("WHERE id IN ({})", vec!["?"; ids.len()].join(", ")),
+        None => String::new(),
+    };
+    DB.with(|conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT * FROM cards {} ORDER BY created_at, id",
+            filter
+        ))?;
+        let cards = stmt
+            .query_map(params_from_iter(ids.unwrap_or_default()), read_card)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(cards)
+    })
+}
+
+/// 今日待复习的卡片数
+pub fn due_count() -> Result<u64, String> {
+    DB.with(|conn| {
+        conn.query_row(
+            "SELECT COUNT(*) FROM cards WHERE due_at < ?1",
+            params![end_of_today()],
+            |row| row.get::<_, i64>(0),
+        )
+    })
+    .map(|count| count as u64)
+}
+
+/// 今日待复习的卡片，最早到期的在前
+pub fn due_cards(limit: u64) -> Result<Vec<VocabularyCard>, String> {
+    DB.with(|conn| {
+        let mut stmt =
+            conn.prepare("SELECT * FROM cards WHERE due_at < ?1 ORDER BY due_at, id LIMIT ?2")?;
+        let cards = stmt
+            .query_map(params![end_of_today(), limit.min(500) as i64], read_card)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(cards)
+    })
+}
+
+/// SM-2 算法：`grade` 为 0–5 的回忆质量，3 分及以上视为答对，间隔依次为 1 天、6 天，
+/// 之后每次乘以难度系数；答错时从头开始且不改变难度系数，稍后在当天重新复习
+fn schedule(card: &mut VocabularyCard, grade: u8, now: i64) {
+    if grade >= 3 {
+        card.interval_days = match card.repetitions {
+            0 => 1,
+            1 => 6,
+            _ => (f64::from(card.interval_days) * card.ease_factor).round() as u32,
+        };
+        card.repetitions += 1;
+        let miss = f64::from(5 - grade);
+        card.ease_factor = (card.ease_factor + 0.1 - miss * (0.08 + miss * 0.02)).max(1.3);
+        card.due_at = now + i64::from(card.interval_days) * 86_400;
+    } else {
+        card.repetitions = 0;
+        card.interval_days = 0;
+        card.lapses += 1;
+        card.due_at = now + RELEARN_DELAY_SECS;
+    }
+    card.reviewed_at = Some(now);
+}
+
+/// 记录一次复习并安排下次复习时间
+pub fn grade(id: i64, grade: u8) -> Result<VocabularyCard, String> {
+    if grade > 5 {
+        return Err("Grade must be between 0 and 5".to_string());
+    }
+    DB.with(|conn| {
+        let Some(mut card) = conn
+            .query_row("SELECT * FROM cards WHERE id = ?1", params![id], read_card)
+            .optional()?
+        else {
+            return Ok(None);
+        };
+        schedule(&mut card, grade, now());
+        conn.execute(
+            "UPDATE cards SET repetitions = ?2, interval_days = ?3, ease_factor = ?4,
+             lapses = ?5, due_at = ?6, reviewed_at = ?7 WHERE id = ?1",
+            params![
+                card.id,
+                card.repetitions,
+                card.interval_days,
+                card.ease_factor,
+                card.lapses,
+                card.due_at,
+                card.reviewed_at
+            ],
+        )?;
+        Ok(Some(card))
+    })?
+    .ok_or_else(|| format!("Card {} not found", id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+    const DAY: i64 = 86_400;
+
+    fn card() -> VocabularyCard {
+        VocabularyCard {
+            id: 1,
+            word: "apple".to_string(),
+            translation: "苹果".to_string(),
+            source_lang: "en".to_string(),
+            target_lang: "zh".to_string(),
+            phonetic: None,
+            example: None,
+            created_at: NOW,
+            repetitions: 0,
+            interval_days: 0,
+            ease_factor: 2.5,
+            lapses: 0,
+            due_at: NOW,
+            reviewed_at: None,
+        }
+    }
+
+    #[test]
+    fn correct_answers_grow_the_interval() {
+        let mut card = card();
+        schedule(&mut card, 4, NOW);
+        assert_eq!((card.repetitions, card.interval_days), (1, 1));
+        assert_eq!(card.due_at, NOW + DAY);
+        assert_eq!(card.reviewed_at, Some(NOW));
+
+        schedule(&mut card, 4, NOW);
+        assert_eq!((card.repetitions, card.interval_days), (2, 6));
+
+        // 4 分不改变难度系数，第三次起间隔乘以 2.5
+        schedule(&mut card, 4, NOW);
+        assert_eq!((card.repetitions, card.interval_days), (3, 15));
+        assert!((card.ease_factor - 2.5).abs() < 1e-9);
+        assert_eq!(card.due_at, NOW + 15 * DAY);
+
+        schedule(&mut card, 5, NOW);
+        assert!((card.ease_factor - 2.6).abs() < 1e-9);
+        assert_eq!(card.interval_days, 38);
+    }
+
+    #[test]
+    fn ease_factor_never_drops_below_floor() {
+        let mut card = card();
+        for _ in 0..10 {
+            schedule(&mut card, 3, NOW);
+        }
+        assert!((card.ease_factor - 1.3).abs() < 1e-9);
+        assert_eq!(card.repetitions, 10);
+    }
+
+    #[test]
+    fn lapse_resets_repetitions_and_relearns_soon() {
+        let mut card = card();
+        for _ in 0..3 {
+            schedule(&mut card, 5, NOW);
+        }
+        let ease_factor = card.ease_factor;
+        schedule(&mut card, 2, NOW);
+        assert_eq!(
+            (card.repetitions, card.interval_days, card.lapses),
+            (0, 0, 1)
+        );
+        assert_eq!(card.due_at, NOW + 10 * 60);
+        assert_eq!(card.ease_factor, ease_factor);
+
+        schedule(&mut card, 4, NOW);
+        assert_eq!((card.repetitions, card.interval_days), (1, 1));
+    }
+
+    #[test]
+    fn grade_above_five_is_rejected() {
+        assert_eq!(grade(1, 6).unwrap_err(), "Grade must be between 0 and 5");
+    }
+}
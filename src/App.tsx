@@ -4,10 +4,27 @@ import { listen } from "@tauri-apps/api/event";
 import { getCurrentWindow, LogicalSize, PhysicalPosition, availableMonitors } from "@tauri-apps/api/window";
 import { register, unregister } from "@tauri-apps/plugin-global-shortcut";
 
+interface DictionaryEntry {
+  translation: string;
+  pos: string;
+  back_translations: string[];
+}
+
 interface TranslateResult {
+  // cancelled 表示被新的翻译请求取消，直接忽略
+  status?: "success" | "error" | "cancelled";
   success: boolean;
   text: string;
   error: string | null;
+  // 源语言为自动检测时识别出的语言
+  detected_lang?: string | null;
+  // 选中单个单词时的其他译法，服务不支持词典时为空
+  alternatives?: DictionaryEntry[];
+}
+
+interface VocabularyCard {
+  id: number;
+  word: string;
 }
 
 interface AppSettings {
@@ -21,14 +38,40 @@ interface AppSettings {
 
 type ViewState =
   | { status: "loading" }
-  | { status: "done"; result: TranslateResult };
+  // 流式翻译进行中，text 为已收到的部分译文
+  | { status: "streaming"; requestId: string; text: string }
+  | { status: "done"; result: TranslateResult }
+  // 对比模式，results 按服务返回的先后排列
+  | { status: "compare"; requestId: string; results: CompareResult[]; finished: boolean };
 
 interface TranslateEvent {
+  request_id: string;
   text: string;
+  // 选中的是单词或短语，可以加入生词本
+  starrable: boolean;
   x: number;
   y: number;
 }
 
+interface TranslateChunkEvent {
+  request_id: string;
+  delta: string;
+}
+
+interface TranslateDoneEvent {
+  request_id: string;
+  result: TranslateResult;
+}
+
+interface CompareResult {
+  request_id: string;
+  provider: string;
+  latency_ms: number;
+  success: boolean;
+  text: string;
+  error: string | null;
+}
+
 function App() {
   const [view, setView] = useState<ViewState | null>(null);
   const [popupWidth, setPopupWidth] = useState(300);
@@ -38,9 +81,17 @@ function App() {
   const [sourceLang, setSourceLang] = useState("EN");
   const [targetLang, setTargetLang] = useState("ZH");
   const [copied, setCopied] = useState(false);
+  // 当前选中的单词或短语，不能加入生词本时为 null
+  const [starText, setStarText] = useState<string | null>(null);
+  const [starredId, setStarredId] = useState<number | null>(null);
+  // 当前翻译的原文，对比模式用它重新请求所有服务
+  const [sourceText, setSourceText] = useState("");
   const hideTimer = useRef<number | null>(null);
   const contentRef = useRef<HTMLDivElement>(null);
-  const requestSeq = useRef(0);
+  // 当前翻译请求的 ID，其他请求的事件一律忽略
+  const activeRequest = useRef<string | null>(null);
+  // 当前请求是否已经显示了窗口（收到第一段译文时显示）
+  const popupShown = useRef(false);
   const lastAnchor = useRef<{ x: number; y: number } | null>(null);
   const autoCloseEnabledRef = useRef(autoCloseEnabled);
   const autoCloseTimeoutRef = useRef(autoCloseTimeout);
@@ -191,93 +242,120 @@ function App() {
     return { posX, posY };
   };
 
+  /** 按给定尺寸在锚点附近显示窗口，尺寸不含阴影所需的 padding */
+  const showPopup = async (width: number, height: number) => {
+    const win = getCurrentWindow();
+    setPopupWidth(width);
+    // 添加 padding 空间以显示阴影和圆角
+    const padding = 16; // 8px * 2
+    await win.setSize(new LogicalSize(width + padding, height + padding));
+    const { posX, posY } = await computePosition({ anchor: lastAnchor.current, width: width + padding, height: height + padding });
+    await win.setPosition(new PhysicalPosition(posX, posY));
+    await win.show();
+    await win.setFocus();
+    setIsFirstShow(false); // 窗口显示后，禁用后续动画
+    log("window-show", { posX, posY, width, height });
+  };
+
   useEffect(() => {
     const unlisten = listen<TranslateEvent>("translate-text", async (event) => {
-      const seq = (requestSeq.current += 1);
       clearHideTimer();
 
-      const { text, x, y } = event.payload;
+      const { request_id: requestId, text, starrable, x, y } = event.payload;
+      activeRequest.current = requestId;
+      popupShown.current = false;
       // x, y 是后端传来的物理坐标，直接存储
       lastAnchor.current = { x, y };
-      log("translate-text", { seq, length: text.length, x, y });
+      setSourceText(text);
+      setStarText(starrable ? text.trim() : null);
+      setStarredId(null);
+      log("translate-text", { requestId, length: text.length, x, y });
 
       try {
-        // 先调用翻译 API，不显示窗口
-        log("translate-api", "calling translate API");
-        const res = await invoke<TranslateResult>("translate", { text });
-        if (seq !== requestSeq.current) {
-          log("translate-api", "sequence mismatch, ignoring result");
-          return;
-        }
-
-        // 翻译失败时也显示错误信息，而不是静默
-        if (!res.success) {
-          log("translate-api", "translation failed, showing error", { error: res.error });
-          setView({ status: "done", result: res });
-
-          const win = getCurrentWindow();
-          const width = 300;
-          setPopupWidth(width);
-          const padding = 16;
-          const initialHeight = 100;
-          await win.setSize(new LogicalSize(width + padding, initialHeight + padding));
-          const { posX, posY } = await computePosition({ anchor: { x, y }, width: width + padding, height: initialHeight + padding });
-          await win.setPosition(new PhysicalPosition(posX, posY));
-          await win.show();
-          await win.setFocus();
-          setIsFirstShow(false);
-          startHideTimer();
-          return;
-        }
+        // 译文通过 translate-chunk / translate-done 事件到达，收到第一段时才显示窗口
+        log("translate-api", "calling translate_stream");
+        await invoke<TranslateResult>("translate_stream", { text, requestId });
+      } catch (e) {
+        if (requestId !== activeRequest.current) return;
+        log("translate-error", { requestId, error: String(e) });
+        // 翻译异常时显示错误
+        setView({ status: "done", result: { success: false, text: "", error: `翻译请求异常: ${String(e)}` } });
+        await showPopup(300, 100);
+        startHideTimer();
+      }
+    });
 
-        if (!res.text || res.text.trim() === "") {
-          log("translate-api", "empty result, not showing window");
-          return;
-        }
+    // 流式译文：追加到当前视图，第一段到达时显示窗口
+    const unlistenChunk = listen<TranslateChunkEvent>("translate-chunk", async (event) => {
+      const { request_id: requestId, delta } = event.payload;
+      if (requestId !== activeRequest.current) return;
+
+      setView((prev) => ({
+        status: "streaming",
+        requestId,
+        text: (prev?.status === "streaming" && prev.requestId === requestId ? prev.text : "") + delta,
+      }));
+
+      if (!popupShown.current) {
+        popupShown.current = true;
+        log("translate-chunk", "first chunk received, showing window", { requestId });
+        await showPopup(getPopupWidth(delta.length), 140); // 容纳头部 + 两行内容
+      }
+    });
 
-        log("translate-api", "translation successful, showing window");
+    const unlistenDone = listen<TranslateDoneEvent>("translate-done", async (event) => {
+      const { request_id: requestId, result: res } = event.payload;
+      if (res.status === "cancelled" || requestId !== activeRequest.current) {
+        log("translate-done", "stale or cancelled request, ignoring result", { requestId });
+        return;
+      }
 
-        // 翻译成功，准备显示窗口
+      // 翻译失败时也显示错误信息，而不是静默
+      if (!res.success) {
+        log("translate-done", "translation failed, showing error", { error: res.error });
         setView({ status: "done", result: res });
+        if (!popupShown.current) {
+          popupShown.current = true;
+          await showPopup(300, 100);
+        }
+        startHideTimer();
+        return;
+      }
 
-        const win = getCurrentWindow();
-        const width = getPopupWidth(res.text.length);
-        setPopupWidth(width);
-
-        // 添加 padding 空间以显示阴影和圆角
-        const padding = 16; // 8px * 2
-        const initialHeight = 140; // 容纳头部 + 两行内容
-        await win.setSize(new LogicalSize(width + padding, initialHeight + padding));
+      if (!res.text || res.text.trim() === "") {
+        log("translate-done", "empty result, not showing window");
+        if (popupShown.current) hideWindow();
+        return;
+      }
 
-        // 计算物理坐标位置
-        const { posX, posY } = await computePosition({ anchor: { x, y }, width: width + padding, height: initialHeight + padding });
-        await win.setPosition(new PhysicalPosition(posX, posY));
+      // 最终结果带有检测语言、词典释义等信息，替换流式拼出的文本
+      setView({ status: "done", result: res });
+      if (!popupShown.current) {
+        popupShown.current = true;
+        await showPopup(getPopupWidth(res.text.length), 140);
+      }
+      startHideTimer();
+      log("translate-result", { requestId, success: res.success, length: res.text.length });
+    });
 
-        await win.show();
-        await win.setFocus();
-        setIsFirstShow(false); // 窗口显示后，禁用后续动画
-        startHideTimer();
-        log("window-show", { posX, posY, resultLength: res.text.length });
+    // 对比模式：每个服务返回后追加一行
+    const unlistenCompare = listen<CompareResult>("translate-compare-result", (event) => {
+      const result = event.payload;
+      if (result.request_id !== activeRequest.current) return;
+      log("translate-compare-result", { provider: result.provider, latency: result.latency_ms, success: result.success });
+      setView((prev) =>
+        prev?.status === "compare" && prev.requestId === result.request_id
+          ? { ...prev, results: [...prev.results, result] }
+          : prev
+      );
+    });
 
-        log("translate-result", { seq, success: res.success, length: res.text.length });
-      } catch (e) {
-        if (seq !== requestSeq.current) return;
-        log("translate-error", { seq, error: String(e) });
-        // 翻译异常时显示错误
-        setView({ status: "done", result: { success: false, text: "", error: `翻译请求异常: ${String(e)}` } });
-        const win = getCurrentWindow();
-        const width = 300;
-        setPopupWidth(width);
-        const padding = 16;
-        const initialHeight = 100;
-        await win.setSize(new LogicalSize(width + padding, initialHeight + padding));
-        const { posX, posY } = await computePosition({ anchor: lastAnchor.current, width: width + padding, height: initialHeight + padding });
-        await win.setPosition(new PhysicalPosition(posX, posY));
-        await win.show();
-        await win.setFocus();
-        setIsFirstShow(false);
-        startHideTimer();
-      }
+    const unlistenCompareDone = listen<string>("translate-compare-done", (event) => {
+      const requestId = event.payload;
+      if (requestId !== activeRequest.current) return;
+      setView((prev) =>
+        prev?.status === "compare" && prev.requestId === requestId ? { ...prev, finished: true } : prev
+      );
     });
 
     // 监听文本获取失败事件
@@ -313,6 +391,10 @@ function App() {
 
     return () => {
       unlisten.then((f) => f());
+      unlistenChunk.then((f) => f());
+      unlistenDone.then((f) => f());
+      unlistenCompare.then((f) => f());
+      unlistenCompareDone.then((f) => f());
       unlistenError.then((f) => f());
       unlistenShortcut.then((f) => f());
       clearHideTimer();
@@ -415,15 +497,22 @@ function App() {
   }, [view, autoCloseEnabled, autoCloseTimeout]);
 
   useLayoutEffect(() => {
-    // 只在翻译完成后才调整窗口高度，避免 loading 状态时的布局跳动
-    if (!view || view.status !== "done" || !contentRef.current) return;
+    // 只在有译文后才调整窗口高度，避免 loading 状态时的布局跳动；流式译文每次追加后都重新测量
+    if (!view || view.status === "loading" || !contentRef.current) return;
     const win = getCurrentWindow();
 
     const raf = window.requestAnimationFrame(async () => {
       if (!contentRef.current) return;
 
       // 根据翻译结果长度重新计算宽度
-      const resultLength = view.result.success ? view.result.text.length : 0;
+      const resultLength =
+        view.status === "streaming"
+          ? view.text.length
+          : view.status === "compare"
+            ? Math.max(0, ...view.results.map((r) => r.text.length))
+            : view.result.success
+              ? view.result.text.length
+              : 0;
       const width = getPopupWidth(resultLength);
       setPopupWidth(width); // 更新状态以便下次使用
 
@@ -433,7 +522,7 @@ function App() {
       if (!header || !content) return;
 
       // 测量内部实际内容元素的高度（而不是容器的高度）
-      const contentInner = content.querySelector('.trans-text, .loading-text, .error-text') as HTMLElement;
+      const contentInner = content.querySelector('.compare-list, .trans-text, .loading-text, .error-text') as HTMLElement;
       if (!contentInner) return;
 
       const headerHeight = header.offsetHeight;
@@ -469,6 +558,44 @@ function App() {
     }
   };
 
+  /** 用当前原文同时请求所有对比服务，结果通过 translate-compare-result 事件逐个到达 */
+  const startCompare = async () => {
+    if (!sourceText) return;
+    clearHideTimer();
+    const requestId = crypto.randomUUID();
+    activeRequest.current = requestId;
+    setView({ status: "compare", requestId, results: [], finished: false });
+    log("startCompare", { requestId, length: sourceText.length });
+    try {
+      await invoke<CompareResult[]>("translate_compare", { text: sourceText, requestId });
+    } catch (e) {
+      if (requestId !== activeRequest.current) return;
+      log("startCompare", "failed", e);
+      setView({ status: "done", result: { success: false, text: "", error: `对比翻译请求异常: ${String(e)}` } });
+    }
+  };
+
+  const toggleStar = async () => {
+    if (view?.status !== "done" || !view.result.success || !starText) return;
+    try {
+      if (starredId !== null) {
+        await invoke("remove_vocabulary", { id: starredId });
+        setStarredId(null);
+      } else {
+        const card = await invoke<VocabularyCard>("add_vocabulary", {
+          word: starText,
+          translation: view.result.text,
+          sourceLang: sourceLang.toLowerCase() === "auto" ? view.result.detected_lang ?? sourceLang : sourceLang,
+          targetLang,
+        });
+        setStarredId(card.id);
+      }
+      log("toggleStar", { word: starText });
+    } catch (e) {
+      log("toggleStar", "failed", e);
+    }
+  };
+
   if (!view) {
     log("render", "view is null, returning null");
     return null;
@@ -494,22 +621,43 @@ function App() {
               </svg>
               <span className="lang-badge">{sourceLang} → {targetLang}</span>
             </div>
-            <button
-              onClick={copyText}
-              className={`copy-btn ${copied ? 'copied' : ''}`}
-              style={{
-                visibility: view.status === "done" && view.result.success ? "visible" : "hidden"
-              }}
-            >
-              <svg className="h-3.5 w-3.5" fill="none" viewBox="0 0 24 24" stroke="currentColor" strokeWidth={2.5}>
-                {copied ? (
-                  <path strokeLinecap="round" strokeLinejoin="round" d="M5 13l4 4L19 7" />
-                ) : (
-                  <path strokeLinecap="round" strokeLinejoin="round" d="M8 16H6a2 2 0 01-2-2V6a2 2 0 012-2h8a2 2 0 012 2v2m-6 12h8a2 2 0 002-2v-8a2 2 0 00-2-2h-8a2 2 0 00-2 2v8a2 2 0 002 2z" />
-                )}
-              </svg>
-              <span>{copied ? '已复制' : '复制'}</span>
-            </button>
+            <div className="flex items-center gap-1">
+              {sourceText && view.status === "done" && (
+                <button onClick={startCompare} className="copy-btn" title="同时请求所有对比服务">
+                  <svg className="h-3.5 w-3.5" fill="none" viewBox="0 0 24 24" stroke="currentColor" strokeWidth={2}>
+                    <path strokeLinecap="round" strokeLinejoin="round" d="M9 17V7m0 10a2 2 0 01-2 2H5a2 2 0 01-2-2V7a2 2 0 012-2h2a2 2 0 012 2m0 10a2 2 0 002 2h2a2 2 0 002-2M9 7a2 2 0 012-2h2a2 2 0 012 2m0 10V7m0 10a2 2 0 002 2h2a2 2 0 002-2V7a2 2 0 00-2-2h-2a2 2 0 00-2 2" />
+                  </svg>
+                  <span>对比</span>
+                </button>
+              )}
+              {starText && view.status === "done" && view.result.success && (
+                <button
+                  onClick={toggleStar}
+                  className={`copy-btn ${starredId !== null ? 'copied' : ''}`}
+                  title={starredId !== null ? '移出生词本' : '加入生词本'}
+                >
+                  <svg className="h-3.5 w-3.5" fill={starredId !== null ? "currentColor" : "none"} viewBox="0 0 24 24" stroke="currentColor" strokeWidth={2}>
+                    <path strokeLinecap="round" strokeLinejoin="round" d="M11.48 3.5a.56.56 0 011.04 0l2.13 5.11a.56.56 0 00.47.35l5.52.44c.5.04.7.66.32.99l-4.2 3.6a.56.56 0 00-.18.56l1.28 5.39a.56.56 0 01-.84.61l-4.73-2.89a.56.56 0 00-.58 0l-4.73 2.89a.56.56 0 01-.84-.61l1.28-5.39a.56.56 0 00-.18-.56l-4.2-3.6a.56.56 0 01.32-.99l5.52-.44a.56.56 0 00.47-.35L11.48 3.5z" />
+                  </svg>
+                </button>
+              )}
+              <button
+                onClick={copyText}
+                className={`copy-btn ${copied ? 'copied' : ''}`}
+                style={{
+                  visibility: view.status === "done" && view.result.success ? "visible" : "hidden"
+                }}
+              >
+                <svg className="h-3.5 w-3.5" fill="none" viewBox="0 0 24 24" stroke="currentColor" strokeWidth={2.5}>
+                  {copied ? (
+                    <path strokeLinecap="round" strokeLinejoin="round" d="M5 13l4 4L19 7" />
+                  ) : (
+                    <path strokeLinecap="round" strokeLinejoin="round" d="M8 16H6a2 2 0 01-2-2V6a2 2 0 012-2h8a2 2 0 012 2v2m-6 12h8a2 2 0 002-2v-8a2 2 0 00-2-2h-8a2 2 0 00-2 2v8a2 2 0 002 2z" />
+                  )}
+                </svg>
+                <span>{copied ? '已复制' : '复制'}</span>
+              </button>
+            </div>
           </div>
         </div>
 
@@ -520,8 +668,49 @@ function App() {
               <span className="spinner"></span>
               <span>翻译中…</span>
             </div>
+          ) : view.status === "compare" ? (
+            <ul className="compare-list">
+              {view.results.map((r) => (
+                <li key={r.provider}>
+                  <div className="compare-meta">
+                    <span className="compare-provider">{r.provider}</span>
+                    <span>{r.latency_ms} ms</span>
+                  </div>
+                  {r.success ? (
+                    <p className="trans-text">{r.text}</p>
+                  ) : (
+                    <p className="error-text">{r.error}</p>
+                  )}
+                </li>
+              ))}
+              {!view.finished && (
+                <li className="loading-text">
+                  <span className="spinner"></span>
+                  <span>等待其他服务…</span>
+                </li>
+              )}
+            </ul>
+          ) : view.status === "streaming" ? (
+            <div className="trans-text">
+              <p>{view.text}</p>
+            </div>
           ) : view.result.success ? (
-            <p className="trans-text">{view.result.text}</p>
+            <div className="trans-text">
+              <p>{view.result.text}</p>
+              {view.result.alternatives && view.result.alternatives.length > 0 && (
+                <ul className="dict-list">
+                  {view.result.alternatives.map((entry, i) => (
+                    <li key={i}>
+                      <span className="dict-pos">{entry.pos.toLowerCase()}</span>
+                      <span>{entry.translation}</span>
+                      {entry.back_translations.length > 0 && (
+                        <span className="dict-back">{entry.back_translations.slice(0, 3).join(", ")}</span>
+                      )}
+                    </li>
+                  ))}
+                </ul>
+              )}
+            </div>
           ) : (
             <div className="error-text">
               <svg className="h-4 w-4" fill="none" viewBox="0 0 24 24" stroke="currentColor" strokeWidth={2}>
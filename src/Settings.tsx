@@ -34,6 +34,34 @@ interface AppSettings {
   auto_start: boolean;
 }
 
+interface Language {
+  code: string;
+  name: string;
+}
+
+interface ProviderInfo {
+  id: string;
+  languages: Language[];
+}
+
+// 翻译服务无法提供语言列表时使用
+const DEFAULT_LANGUAGES: Language[] = [
+  { code: "ZH", name: "简体中文" },
+  { code: "EN", name: "English" },
+  { code: "JA", name: "日本語" },
+  { code: "KO", name: "한국어" },
+  { code: "FR", name: "Français" },
+  { code: "DE", name: "Deutsch" },
+  { code: "ES", name: "Español" },
+  { code: "RU", name: "Русский" },
+  { code: "IT", name: "Italiano" },
+  { code: "PT", name: "Português" },
+  { code: "AR", name: "العربية" },
+  { code: "NL", name: "Nederlands" },
+  { code: "PL", name: "Polski" },
+  { code: "TR", name: "Türkçe" },
+];
+
 const DEFAULT_AUTO_CLOSE_TIMEOUT = 1500;
 const ALLOWED_AUTO_CLOSE_TIMEOUTS = new Set([0, 1000, 1500, 2000, 3000]);
 
@@ -53,11 +81,24 @@ function Settings() {
   const [shortcut, setShortcut] = useState("Ctrl+Q");
   const [isRecording, setIsRecording] = useState(false);
   const [autoStart, setAutoStart] = useState(false);
+  const [languages, setLanguages] = useState<Language[]>(DEFAULT_LANGUAGES);
 
   useEffect(() => {
     loadSettings();
+    loadLanguages();
   }, []);
 
+  const loadLanguages = async () => {
+    try {
+      const info = await invoke<ProviderInfo>("get_provider_info");
+      if (info.languages.length > 0) {
+        setLanguages(info.languages);
+      }
+    } catch (e) {
+      console.error("Failed to load provider languages:", e);
+    }
+  };
+
   const loadSettings = async () => {
     try {
       const settings = await invoke<AppSettings>("get_settings");
@@ -199,20 +240,12 @@ function Settings() {
                   <SelectValue />
                 </SelectTrigger>
                 <SelectContent side="bottom" avoidCollisions={false} className="bg-white">
-                  <SelectItem value="EN">English (EN)</SelectItem>
-                  <SelectItem value="ZH">简体中文 (ZH)</SelectItem>
-                  <SelectItem value="JA">日本語 (JA)</SelectItem>
-                  <SelectItem value="KO">한국어 (KO)</SelectItem>
-                  <SelectItem value="FR">Français (FR)</SelectItem>
-                  <SelectItem value="DE">Deutsch (DE)</SelectItem>
-                  <SelectItem value="ES">Español (ES)</SelectItem>
-                  <SelectItem value="RU">Русский (RU)</SelectItem>
-                  <SelectItem value="IT">Italiano (IT)</SelectItem>
-                  <SelectItem value="PT">Português (PT)</SelectItem>
-                  <SelectItem value="AR">العربية (AR)</SelectItem>
-                  <SelectItem value="NL">Nederlands (NL)</SelectItem>
-                  <SelectItem value="PL">Polski (PL)</SelectItem>
-                  <SelectItem value="TR">Türkçe (TR)</SelectItem>
+                  <SelectItem value="auto">自动检测</SelectItem>
+                  {languages.map((lang) => (
+                    <SelectItem key={lang.code} value={lang.code}>
+                      {lang.name} ({lang.code})
+                    </SelectItem>
+                  ))}
                 </SelectContent>
               </Select>
 
@@ -223,20 +256,11 @@ function Settings() {
                   <SelectValue />
                 </SelectTrigger>
                 <SelectContent side="bottom" avoidCollisions={false} className="bg-white">
-                  <SelectItem value="ZH">简体中文 (ZH)</SelectItem>
-                  <SelectItem value="EN">English (EN)</SelectItem>
-                  <SelectItem value="JA">日本語 (JA)</SelectItem>
-                  <SelectItem value="KO">한국어 (KO)</SelectItem>
-                  <SelectItem value="FR">Français (FR)</SelectItem>
-                  <SelectItem value="DE">Deutsch (DE)</SelectItem>
-                  <SelectItem value="ES">Español (ES)</SelectItem>
-                  <SelectItem value="RU">Русский (RU)</SelectItem>
-                  <SelectItem value="IT">Italiano (IT)</SelectItem>
-                  <SelectItem value="PT">Português (PT)</SelectItem>
-                  <SelectItem value="AR">العربية (AR)</SelectItem>
-                  <SelectItem value="NL">Nederlands (NL)</SelectItem>
-                  <SelectItem value="PL">Polski (PL)</SelectItem>
-                  <SelectItem value="TR">Türkçe (TR)</SelectItem>
+                  {languages.map((lang) => (
+                    <SelectItem key={lang.code} value={lang.code}>
+                      {lang.name} ({lang.code})
+                    </SelectItem>
+                  ))}
                 </SelectContent>
               </Select>
             </div>